@@ -12,7 +12,7 @@ function fish_preexec --on-event fish_preexec
             memy  \
                 --config denied_files_warn_on_note=false \
                 --config missing_files_warn_on_note=false \
-                note "$expanded" &
+                note --source fish "$expanded" &
         end
     end
 end
@@ -6,7 +6,7 @@ vim.api.nvim_create_autocmd({ "BufReadPre", "BufWritePost" }, {
             file = file:sub(7)
         end
 
-        vim.system({ "memy", "note", file }, { detach = true }, function(out)
+        vim.system({ "memy", "note", "--source", "nvim", file }, { detach = true }, function(out)
             vim.schedule(function()
                 if out.code ~= 0 then
                     vim.notify(
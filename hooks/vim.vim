@@ -6,7 +6,7 @@ augroup END
 function! s:RunMemyNote(file) abort
   let l:file = a:file
 
-  call job_start(['memy', 'note', l:file], {
+  call job_start(['memy', 'note', '--source', 'vim', l:file], {
         \ 'exit_cb': function('s:OnMemyExit')
         \ })
 endfunction
@@ -0,0 +1,357 @@
+use core::error::Error;
+use core::time::Duration;
+use ignore::gitignore::Gitignore;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::os::unix::fs::PermissionsExt as _;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::SystemTime;
+use tracing::{debug, info, instrument, warn};
+
+use crate::note::{self, Skip};
+use crate::utils::config;
+use crate::utils::db;
+use crate::utils::types::UnixTimestamp;
+
+const SOCKET_FILENAME: &str = "memy.sock";
+
+/// How long the writer waits for more notes before committing a batch.
+const BATCH_WINDOW: Duration = Duration::from_millis(20);
+
+const MAX_BATCH_SIZE: usize = 256;
+
+/// How long `memy note` waits for the daemon to connect, take the request and answer it.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Config options that only decide what `memy note` warns about, which the client handles itself.
+const CLIENT_SIDE_OPTIONS: &[&str] = &["missing_files_warn_on_note", "denied_files_warn_on_note"];
+
+/// Returns where the daemon's socket lives, or `None` if `XDG_RUNTIME_DIR` isn't set.
+pub fn get_socket_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("memy").join(SOCKET_FILENAME))
+}
+
+/// What the daemon and a client must agree on for the daemon to note paths on the client's behalf.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Identity {
+    db_file: PathBuf,
+    config_file: PathBuf,
+    config_overrides: Vec<(String, String)>,
+}
+
+impl Identity {
+    fn current() -> Self {
+        Self {
+            db_file: db::get_db_file_path(),
+            config_file: config::get_config_file_path(),
+            config_overrides: config::get_config_overrides()
+                .iter()
+                .filter(|(key, _)| !CLIENT_SIDE_OPTIONS.contains(&key.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NoteRequest {
+    identity: Identity,
+    paths: Vec<String>,
+    source: Option<String>,
+    cwd: Option<String>,
+    timestamp: UnixTimestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum NoteResponse {
+    Noted { skipped: Vec<Skip> },
+    Refused { reason: String },
+}
+
+/// Notes waiting for the writer thread, with a channel to report back on once committed.
+struct PendingNote {
+    paths: Vec<PathBuf>,
+    timestamp: UnixTimestamp,
+    source: Option<String>,
+    cwd: Option<String>,
+    done: mpsc::Sender<Result<(), String>>,
+}
+
+/// State shared by the threads serving connections.
+struct Daemon {
+    identity: Identity,
+    config_modified: Option<SystemTime>,
+    denylist_matcher: Gitignore,
+    writer: mpsc::Sender<PendingNote>,
+}
+
+fn config_modified() -> Option<SystemTime> {
+    fs::metadata(config::get_config_file_path())
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+impl Daemon {
+    fn check(&self, request: &NoteRequest) -> Result<(), String> {
+        if request.identity != self.identity {
+            return Err(
+                "the daemon uses a different database, config file or --config overrides"
+                    .to_owned(),
+            );
+        }
+        if config_modified() != self.config_modified {
+            warn!("The config file has changed, restart the memy daemon to pick it up");
+            return Err("the config file has changed since the daemon started".to_owned());
+        }
+        Ok(())
+    }
+
+    fn note(&self, request: NoteRequest) -> NoteResponse {
+        let mut paths = Vec::new();
+        let mut skipped = Vec::new();
+        for raw_path in &request.paths {
+            match note::preprocess_path(raw_path, &self.denylist_matcher) {
+                Ok(Ok(path)) => paths.push(path),
+                Ok(Err(skip)) => skipped.push(skip),
+                Err(err) => {
+                    return NoteResponse::Refused {
+                        reason: format!("Cannot note {raw_path}: {err}"),
+                    };
+                }
+            }
+        }
+
+        if paths.is_empty() {
+            return NoteResponse::Noted { skipped };
+        }
+
+        let (done, committed) = mpsc::channel();
+        let pending = PendingNote {
+            paths,
+            timestamp: request.timestamp,
+            source: request.source,
+            cwd: request.cwd,
+            done,
+        };
+        let result = self
+            .writer
+            .send(pending)
+            .map_err(|_| "the database writer has stopped".to_owned())
+            .and_then(|()| {
+                committed
+                    .recv()
+                    .unwrap_or_else(|_| Err("the database writer has stopped".to_owned()))
+            });
+
+        match result {
+            Ok(()) => NoteResponse::Noted { skipped },
+            Err(reason) => NoteResponse::Refused { reason },
+        }
+    }
+
+    fn serve(&self, stream: &UnixStream) -> Result<(), Box<dyn Error>> {
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        let request: NoteRequest = serde_json::from_str(&line)?;
+
+        let response = match self.check(&request) {
+            Ok(()) => self.note(request),
+            Err(reason) => NoteResponse::Refused { reason },
+        };
+
+        let mut reply = serde_json::to_string(&response)?;
+        reply.push('\n');
+        let mut writer = stream;
+        writer.write_all(reply.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn write_batch(conn: &mut Connection, batch: &[PendingNote]) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    for pending in batch {
+        for path in &pending.paths {
+            note::insert_path(
+                &tx,
+                path,
+                pending.timestamp,
+                pending.source.as_deref(),
+                pending.cwd.as_deref(),
+            )?;
+        }
+    }
+    tx.commit()
+}
+
+/// Commits notes as they arrive, gathering those that arrive close together into one transaction.
+fn run_writer(mut conn: Connection, receiver: &mpsc::Receiver<PendingNote>) {
+    while let Ok(first) = receiver.recv() {
+        let mut batch = vec![first];
+        while batch.len() < MAX_BATCH_SIZE {
+            match receiver.recv_timeout(BATCH_WINDOW) {
+                Ok(pending) => batch.push(pending),
+                Err(_) => break,
+            }
+        }
+
+        let result = write_batch(&mut conn, &batch).map_err(|err| err.to_string());
+        debug!("Committed a batch of {} notes", batch.len());
+        for pending in batch {
+            if pending.done.send(result.clone()).is_err() {
+                debug!("A client stopped waiting before its notes were committed");
+            }
+        }
+    }
+}
+
+fn bind(socket_path: &Path) -> Result<UnixListener, Box<dyn Error>> {
+    if let Some(dir) = socket_path.parent() {
+        fs::create_dir_all(dir)?;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+
+    if socket_path.exists() {
+        if UnixStream::connect(socket_path).is_ok() {
+            return Err(format!(
+                "A memy daemon is already listening on {}",
+                socket_path.display()
+            )
+            .into());
+        }
+        debug!("Removing stale socket {}", socket_path.display());
+        fs::remove_file(socket_path)?;
+    }
+
+    Ok(UnixListener::bind(socket_path)?)
+}
+
+#[instrument(level = "trace")]
+pub fn command() -> Result<(), Box<dyn Error>> {
+    let socket_path = get_socket_path()
+        .ok_or("XDG_RUNTIME_DIR is not set, so there is nowhere to put the daemon's socket")?;
+    let listener = bind(&socket_path)?;
+
+    let conn = db::open()?;
+    let (writer, receiver) = mpsc::channel();
+    thread::spawn(move || run_writer(conn, &receiver));
+
+    let daemon = Arc::new(Daemon {
+        identity: Identity::current(),
+        config_modified: config_modified(),
+        denylist_matcher: config::get_denylist_matcher(),
+        writer,
+    });
+
+    info!("memy daemon listening on {}", socket_path.display());
+
+    for incoming in listener.incoming() {
+        match incoming {
+            Ok(stream) => {
+                let shared = Arc::clone(&daemon);
+                thread::spawn(move || {
+                    if let Err(err) = shared.serve(&stream) {
+                        warn!("Failed to serve a memy note request: {err}");
+                    }
+                });
+            }
+            Err(err) => warn!("Failed to accept a connection: {err}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// What became of a note handed to the daemon.
+pub enum Forwarded {
+    /// The daemon noted the paths, apart from these skipped ones.
+    Noted(Vec<Skip>),
+    /// The daemon never noted anything, for this reason, so the caller should note the paths
+    /// itself.
+    NotNoted(String),
+}
+
+/// Hands paths to a running daemon to note. Fails only if the request was sent but no answer came
+/// back, when the paths may or may not have been noted, so noting them again could count them
+/// twice.
+pub fn forward_note(
+    raw_paths: &[String],
+    source: Option<&str>,
+    cwd: Option<&str>,
+    timestamp: UnixTimestamp,
+) -> Result<Forwarded, Box<dyn Error>> {
+    let Some(socket_path) = get_socket_path() else {
+        return Ok(Forwarded::NotNoted("XDG_RUNTIME_DIR is not set".to_owned()));
+    };
+    let Some(cwd_path) = cwd else {
+        return Ok(Forwarded::NotNoted(
+            "cannot resolve relative paths without a current directory".to_owned(),
+        ));
+    };
+    let stream = match UnixStream::connect(&socket_path).and_then(|stream| {
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+        Ok(stream)
+    }) {
+        Ok(stream) => stream,
+        Err(err) => return Ok(Forwarded::NotNoted(err.to_string())),
+    };
+
+    // The daemon has its own current directory, so relative paths are resolved here
+    let paths = raw_paths
+        .iter()
+        .map(|raw_path| {
+            if raw_path.starts_with('~') || Path::new(raw_path).is_absolute() {
+                raw_path.clone()
+            } else {
+                Path::new(cwd_path)
+                    .join(raw_path)
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        })
+        .collect();
+
+    let request = NoteRequest {
+        identity: Identity::current(),
+        paths,
+        source: source.map(str::to_owned),
+        cwd: cwd.map(str::to_owned),
+        timestamp,
+    };
+    let mut message = serde_json::to_string(&request)?;
+    message.push('\n');
+    let mut writer = &stream;
+    // The daemon only acts on a whole line, so nothing is noted unless this succeeds
+    if let Err(err) = writer.write_all(message.as_bytes()) {
+        return Ok(Forwarded::NotNoted(err.to_string()));
+    }
+
+    let mut line = String::new();
+    let response = BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|err| err.to_string())
+        .and_then(|_| serde_json::from_str(&line).map_err(|err| err.to_string()))
+        .map_err(|err| {
+            format!(
+                "No answer from the memy daemon at {}, so the paths may not have been noted: {err}",
+                socket_path.display()
+            )
+        })?;
+    match response {
+        NoteResponse::Noted { skipped } => {
+            info!("Noted through the memy daemon at {}", socket_path.display());
+            Ok(Forwarded::Noted(skipped))
+        }
+        NoteResponse::Refused { reason } => {
+            Ok(Forwarded::NotNoted(format!("the daemon refused: {reason}")))
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use colored::Colorize as _;
+use core::error::Error;
+use core::fmt;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::io::{Write as _, stdout};
+use std::path::{Path, PathBuf};
+use tracing::{info, instrument};
+
+use crate::hooks;
+use crate::utils::cli::{DoctorArgs, MergeStrategy};
+use crate::utils::config;
+use crate::utils::db;
+use crate::utils::db::TablePathsEntry;
+use crate::utils::merge;
+use crate::utils::time::get_timestamp_now;
+
+/// Shell rc files the hooks are installed into, with the hook and the command that installs each
+/// one.
+const HOOK_RC_FILES: [(&str, &str, &str, &str); 3] = [
+    (
+        "bash",
+        ".bashrc",
+        "bash",
+        "echo 'source <(memy hook bash)' >> ~/.bashrc",
+    ),
+    (
+        "zsh",
+        ".zshrc",
+        "zsh",
+        "echo 'eval $(memy hook zsh)' >> ~/.zshrc",
+    ),
+    (
+        "fish",
+        ".config/fish/config.fish",
+        "fish.fish",
+        "memy hook fish.fish >> ~/.config/fish/config.fish",
+    ),
+];
+
+/// Where `shell` reads `rc_file` from, which for zsh is under `$ZDOTDIR` if it's set.
+fn rc_path(home: &Path, shell: &str, rc_file: &str) -> PathBuf {
+    let zdotdir = std::env::var_os("ZDOTDIR").filter(|dir| !dir.is_empty());
+    match zdotdir {
+        Some(dir) if shell == "zsh" => PathBuf::from(dir).join(rc_file),
+        _ => home.join(rc_file),
+    }
+}
+
+/// Returns true if `contents` loads the `hook_name` hook through `memy hook` (or, for zsh, as a
+/// plugin), or has the hook pasted in.
+fn has_hook(contents: &str, hook_name: &str) -> bool {
+    let loads_hook = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| {
+            line.contains(&format!("memy hook {hook_name}"))
+                || (hook_name == "zsh" && line.contains("andrewferrier/memy"))
+        });
+
+    // A pasted hook is recognised by its first line of code
+    let pasted = hooks::get_hook(hook_name).is_some_and(|hook| {
+        hook.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(|first_line| contents.lines().any(|line| line.trim() == first_line))
+    });
+
+    loads_hook || pasted
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Pass => "PASS".green(),
+            Self::Warn => "WARN".yellow(),
+            Self::Fail => "FAIL".red(),
+        };
+
+        write!(f, "[{label}]")
+    }
+}
+
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    suggestion: Option<String>,
+}
+
+impl Check {
+    const fn pass(name: &'static str, detail: String) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail,
+            suggestion: None,
+        }
+    }
+
+    const fn problem(
+        name: &'static str,
+        status: Status,
+        detail: String,
+        suggestion: String,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            detail,
+            suggestion: Some(suggestion),
+        }
+    }
+}
+
+/// Entries that need attention, found by looking at every path in the database.
+#[derive(Default)]
+struct Findings {
+    /// Missing paths old enough to remove under `missing_files_delete_from_db_after`.
+    missing: Vec<String>,
+    /// Missing paths that are kept for now, or for good if the option is negative.
+    missing_kept: usize,
+    /// Paths whose existence couldn't be checked, e.g. for lack of permission.
+    unreadable: Vec<String>,
+    denied: Vec<String>,
+    /// Groups of entries that resolve to the same file, keyed by the canonical path.
+    duplicates: BTreeMap<PathBuf, Vec<TablePathsEntry>>,
+}
+
+fn find_problem_entries(rows: Vec<TablePathsEntry>) -> Findings {
+    let denylist_matcher = config::get_denylist_matcher();
+    let now = get_timestamp_now();
+    let delete_after_secs = i64::from(config::get_missing_files_delete_from_db_after()) * 86400;
+    let mut findings = Findings::default();
+
+    for row in rows {
+        let path = Path::new(&row.path);
+        let resolved = match path.try_exists() {
+            Ok(false) => {
+                if delete_after_secs >= 0 && now - row.last_noted_timestamp > delete_after_secs {
+                    findings.missing.push(row.path);
+                } else {
+                    findings.missing_kept += 1;
+                }
+                continue;
+            }
+            Ok(true) => path.canonicalize(),
+            Err(err) => Err(err),
+        };
+        let Ok(canonical) = resolved else {
+            findings.unreadable.push(row.path);
+            continue;
+        };
+
+        if denylist_matcher
+            .matched_path_or_any_parents(&row.path, canonical.is_dir())
+            .is_ignore()
+        {
+            findings.denied.push(row.path);
+            continue;
+        }
+
+        findings.duplicates.entry(canonical).or_default().push(row);
+    }
+
+    findings.duplicates.retain(|_, entries| entries.len() > 1);
+    findings
+}
+
+fn check_integrity(conn: &Connection) -> Result<Check, Box<dyn Error>> {
+    let problems = db::integrity_check(conn)?;
+
+    Ok(if problems.is_empty() {
+        Check::pass("Database integrity", "ok".to_owned())
+    } else {
+        Check::problem(
+            "Database integrity",
+            Status::Fail,
+            problems.join("; "),
+            "Restore the database from a backup, or move it aside and re-import a `memy export`"
+                .to_owned(),
+        )
+    })
+}
+
+fn check_config_file() -> Check {
+    let config_path = config::get_config_file_path();
+
+    if config_path.exists() {
+        Check::pass("Config file", config_path.display().to_string())
+    } else {
+        Check::pass(
+            "Config file",
+            format!("{} (not present, using defaults)", config_path.display()),
+        )
+    }
+}
+
+fn check_entries(findings: &Findings) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let delete_after_days = config::get_missing_files_delete_from_db_after();
+    checks.push(if !findings.missing.is_empty() {
+        Check::problem(
+            "Missing paths",
+            Status::Warn,
+            format!(
+                "{} paths no longer exist and were last noted over {delete_after_days} days ago",
+                findings.missing.len()
+            ),
+            "Run `memy doctor --fix` to remove them".to_owned(),
+        )
+    } else if findings.missing_kept == 0 {
+        Check::pass("Missing paths", "none".to_owned())
+    } else if delete_after_days < 0 {
+        Check::pass(
+            "Missing paths",
+            format!(
+                "{} paths no longer exist, kept because missing_files_delete_from_db_after is negative",
+                findings.missing_kept
+            ),
+        )
+    } else {
+        Check::pass(
+            "Missing paths",
+            format!(
+                "{} paths no longer exist, kept until {delete_after_days} days after they were last noted",
+                findings.missing_kept
+            ),
+        )
+    });
+
+    if !findings.unreadable.is_empty() {
+        checks.push(Check::problem(
+            "Unreadable paths",
+            Status::Warn,
+            format!(
+                "{} paths couldn't be checked, e.g. {}",
+                findings.unreadable.len(),
+                findings.unreadable[0]
+            ),
+            "Check their permissions; they are left in the database".to_owned(),
+        ));
+    }
+
+    checks.push(if findings.denied.is_empty() {
+        Check::pass("Denied paths", "none".to_owned())
+    } else {
+        Check::problem(
+            "Denied paths",
+            Status::Warn,
+            format!("{} paths match the denylist", findings.denied.len()),
+            "Run `memy doctor --fix` to remove them".to_owned(),
+        )
+    });
+
+    let duplicate_count: usize = findings.duplicates.values().map(Vec::len).sum();
+    checks.push(if duplicate_count == 0 {
+        Check::pass("Duplicate paths", "none".to_owned())
+    } else if config::get_normalize_symlinks_on_note() {
+        Check::problem(
+            "Duplicate paths",
+            Status::Warn,
+            format!(
+                "{duplicate_count} entries are symlinks to the same {} paths",
+                findings.duplicates.len()
+            ),
+            "Run `memy doctor --fix` to merge them".to_owned(),
+        )
+    } else {
+        Check::pass(
+            "Duplicate paths",
+            format!(
+                "{duplicate_count} entries are symlinks to the same {} paths, kept because \
+                 normalize_symlinks_on_note = false",
+                findings.duplicates.len()
+            ),
+        )
+    });
+
+    checks
+}
+
+fn check_hooks() -> Vec<Check> {
+    let Some(home) = std::env::home_dir() else {
+        return vec![Check::problem(
+            "Shell hooks",
+            Status::Warn,
+            "cannot determine home directory".to_owned(),
+            "Set $HOME".to_owned(),
+        )];
+    };
+
+    let checks: Vec<Check> = HOOK_RC_FILES
+        .iter()
+        .filter_map(|(shell, rc_file, hook_name, install_command)| {
+            let rc_path = rc_path(&home, shell, rc_file);
+            let contents = std::fs::read_to_string(&rc_path).ok()?;
+
+            Some(if has_hook(&contents, hook_name) {
+                Check::pass(
+                    "Shell hooks",
+                    format!("{shell} hook found in {}", rc_path.display()),
+                )
+            } else {
+                Check::problem(
+                    "Shell hooks",
+                    Status::Warn,
+                    format!("{shell} hook not found in {}", rc_path.display()),
+                    format!("Run `{install_command}`"),
+                )
+            })
+        })
+        .collect();
+
+    if checks.is_empty() {
+        return vec![Check::problem(
+            "Shell hooks",
+            Status::Warn,
+            "no bash, zsh or fish rc files found".to_owned(),
+            "See `memy hook` for the available hooks".to_owned(),
+        )];
+    }
+
+    checks
+}
+
+/// Folds each group of duplicates into the entry stored under the canonical path, or the most
+/// noted entry if none is, returning how many entries were removed.
+fn merge_duplicates(
+    conn: &Connection,
+    duplicates: BTreeMap<PathBuf, Vec<TablePathsEntry>>,
+) -> Result<usize, Box<dyn Error>> {
+    let mut removed = 0;
+
+    for (canonical, mut entries) in duplicates {
+        entries.sort_by_key(|entry| {
+            (
+                Path::new(&entry.path) != canonical,
+                core::cmp::Reverse(entry.noted_count),
+            )
+        });
+        let mut entries_iter = entries.into_iter();
+        let mut kept = entries_iter.next().expect("Duplicate groups have entries");
+
+        for duplicate in entries_iter {
+            if let merge::MergeOutcome::Update { merged, .. } =
+                merge::merge_entry(Some(&kept), &duplicate, MergeStrategy::Sum)
+            {
+                kept = merged;
+            }
+            db::fold_path_into(conn, &duplicate.path, &kept)?;
+            info!("Merged {} into {}", duplicate.path, kept.path);
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+fn fix(conn: &mut Connection, findings: Findings) -> Result<String, Box<dyn Error>> {
+    let tx = conn.transaction().expect("Cannot start DB transaction");
+    db::delete_paths(&tx, &findings.missing)?;
+    db::delete_paths(&tx, &findings.denied)?;
+    let merged = if config::get_normalize_symlinks_on_note() {
+        merge_duplicates(&tx, findings.duplicates)?
+    } else {
+        0
+    };
+    tx.commit().expect("Cannot commit transaction");
+
+    db::vacuum(conn)?;
+
+    Ok(format!(
+        "removed {} missing and {} denied paths, merged {merged} duplicates, vacuumed the database",
+        findings.missing.len(),
+        findings.denied.len()
+    ))
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &DoctorArgs) -> Result<(), Box<dyn Error>> {
+    let mut db_connection = db::open()?;
+
+    let mut checks = vec![
+        check_integrity(&db_connection)?,
+        Check::pass(
+            "Schema version",
+            db::get_db_version(&db_connection).to_string(),
+        ),
+        Check::pass("Database", db::get_db_file_path().display().to_string()),
+        check_config_file(),
+    ];
+
+    let findings = find_problem_entries(db::get_rows(&db_connection)?);
+    checks.extend(check_entries(&findings));
+    checks.extend(check_hooks());
+
+    let mut stdout_handle = stdout().lock();
+    for check in &checks {
+        writeln!(
+            stdout_handle,
+            "{} {}: {}",
+            check.status, check.name, check.detail
+        )?;
+        if let Some(suggestion) = &check.suggestion {
+            writeln!(stdout_handle, "       {suggestion}")?;
+        }
+    }
+
+    let warnings = checks.iter().filter(|c| c.status == Status::Warn).count();
+    let failures = checks.iter().filter(|c| c.status == Status::Fail).count();
+    writeln!(stdout_handle, "\n{warnings} warnings, {failures} failures")?;
+
+    if args.fix {
+        if failures > 0 {
+            db::close(db_connection)?;
+            return Err("Not fixing anything while the database fails its integrity check".into());
+        }
+
+        let fixed = fix(&mut db_connection, findings)?;
+        writeln!(stdout_handle, "Fixed: {fixed}")?;
+    }
+
+    db::close(db_connection)?;
+
+    if failures > 0 {
+        return Err("memy doctor found problems".into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use core::error::Error;
+use ignore::Match;
+use ignore::gitignore::{Gitignore, Glob};
+use rayon::prelude::*;
+use std::fs::metadata;
+use std::io::{Write as _, stdout};
+use std::path::Path;
+use tracing::instrument;
+
+use crate::utils::cli::ExplainArgs;
+use crate::utils::config;
+use crate::utils::config::FrecencyAlgorithm;
+use crate::utils::db;
+use crate::utils::db::TablePathsEntry;
+use crate::utils::frecency;
+use crate::utils::time::{format_relative, get_iso8601, get_timestamp_now, timestamp_age_hours};
+use crate::utils::types::{Frecency, UnixTimestamp};
+
+const fn algorithm_name(algorithm: FrecencyAlgorithm) -> &'static str {
+    match algorithm {
+        FrecencyAlgorithm::Linear => "linear",
+        FrecencyAlgorithm::Zoxide => "zoxide",
+        FrecencyAlgorithm::Fasd => "fasd",
+        FrecencyAlgorithm::HalfLife => "half-life",
+    }
+}
+
+const fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+/// Why `memy list` leaves a path out, if it does.
+enum Exclusion {
+    Missing,
+    Denied,
+}
+
+fn exclusion(row: &TablePathsEntry, denylist_matcher: &Gitignore) -> Option<Exclusion> {
+    let Ok(meta) = metadata(&row.path) else {
+        return Some(Exclusion::Missing);
+    };
+
+    denylist_matcher
+        .matched_path_or_any_parents(&row.path, meta.is_dir())
+        .is_ignore()
+        .then_some(Exclusion::Denied)
+}
+
+fn describe_missing(row: &TablePathsEntry, now: UnixTimestamp) -> String {
+    if metadata(&row.path).is_ok() {
+        return "no".to_owned();
+    }
+
+    let delete_after_days = config::get_missing_files_delete_from_db_after();
+    if delete_after_days < 0 {
+        "yes, kept in the database because missing_files_delete_from_db_after is negative"
+            .to_owned()
+    } else if now - row.last_noted_timestamp > i64::from(delete_after_days) * 86400 {
+        "yes, removed from the database the next time paths are listed".to_owned()
+    } else {
+        format!(
+            "yes, skipped when listing, and removed from the database {delete_after_days} days after it was last noted"
+        )
+    }
+}
+
+/// Describes which denylist pattern, if any, decides whether `path` is denied.
+fn describe_denylist(path: &str, denylist_matcher: &Gitignore) -> String {
+    let is_dir = metadata(path).is_ok_and(|meta| meta.is_dir());
+    let origin = |glob: &Glob| {
+        if glob.from().is_some() {
+            "the denylist config option"
+        } else {
+            "the default denylist"
+        }
+    };
+
+    match denylist_matcher.matched_path_or_any_parents(path, is_dir) {
+        Match::Ignore(glob) => format!("yes, by '{}' from {}", glob.original(), origin(glob)),
+        Match::Whitelist(glob) => {
+            format!("no, allowed by '{}' from {}", glob.original(), origin(glob))
+        }
+        Match::None => "no".to_owned(),
+    }
+}
+
+/// Ranks `row` the way a plain `memy list` orders paths: pinned paths first, then by frecency.
+fn describe_rank<F>(
+    row: &TablePathsEntry,
+    rows: &[TablePathsEntry],
+    denylist_matcher: &Gitignore,
+    score: F,
+) -> String
+where
+    F: Fn(&TablePathsEntry) -> Frecency + Sync,
+{
+    match exclusion(row, denylist_matcher) {
+        Some(Exclusion::Missing) => "not listed, the path is missing".to_owned(),
+        Some(Exclusion::Denied) => "not listed, the path is denylisted".to_owned(),
+        None => {
+            let row_score = score(row);
+            let listed: Vec<(bool, Frecency)> = rows
+                .par_iter()
+                .filter(|r| exclusion(r, denylist_matcher).is_none())
+                .map(|r| (r.pinned, score(r)))
+                .collect();
+            let ahead = listed
+                .iter()
+                .filter(|(pinned, other_score)| {
+                    (*pinned && !row.pinned) || (*pinned == row.pinned && *other_score > row_score)
+                })
+                .count();
+            format!("{} of {}", ahead + 1, listed.len())
+        }
+    }
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &ExplainArgs) -> Result<(), Box<dyn Error>> {
+    let db_connection = db::open()?;
+    let resolved = db::resolve_stored_paths(&db_connection, core::slice::from_ref(&args.path));
+    let rows = db::get_rows(&db_connection)?;
+    db::close(db_connection)?;
+    let stored_path = resolved?
+        .pop()
+        .expect("One stored path per path to resolve");
+
+    let row = rows
+        .iter()
+        .find(|row| row.path == stored_path)
+        .expect("Resolved paths are in the database");
+
+    let now = get_timestamp_now();
+    let highest_count = rows.iter().map(|r| r.noted_count).max().unwrap_or(0);
+    let oldest_last_noted_timestamp = rows
+        .iter()
+        .map(|r| r.last_noted_timestamp)
+        .min()
+        .unwrap_or(now);
+    let oldest_last_noted_age_hours = timestamp_age_hours(now, oldest_last_noted_timestamp);
+
+    let algorithm = config::get_frecency_algorithm();
+    let lambda = config::get_recency_bias();
+    let scorer = frecency::get_scorer();
+    let cwd_boost = config::get_cwd_boost();
+    #[allow(clippy::float_cmp, reason = "1.0 is exactly the 'no boost' default")]
+    let cwd = if cwd_boost == 1.0 {
+        None
+    } else {
+        Some(std::env::current_dir()?)
+    };
+
+    let score = |r: &TablePathsEntry| -> Frecency {
+        let base = scorer.score(&frecency::FrecencyInput {
+            count: r.noted_count,
+            last_noted_age_hours: timestamp_age_hours(now, r.last_noted_timestamp),
+            highest_count,
+            oldest_last_noted_age_hours,
+        });
+        cwd.as_ref().map_or(base, |dir| {
+            base * frecency::cwd_multiplier(Path::new(&r.path), dir, cwd_boost)
+        })
+    };
+
+    let last_noted_age_hours = timestamp_age_hours(now, row.last_noted_timestamp);
+    let frequency = frecency::normalised_frequency(row.noted_count, highest_count);
+    let recency = frecency::normalised_recency(last_noted_age_hours, oldest_last_noted_age_hours);
+    let row_score = score(row);
+
+    let denylist_matcher = config::get_denylist_matcher();
+    let rank = describe_rank(row, &rows, &denylist_matcher, score);
+
+    let mut stdout_handle = stdout().lock();
+    writeln!(stdout_handle, "Path: {}", row.path)?;
+    writeln!(stdout_handle, "  Noted count: {}", row.noted_count)?;
+    writeln!(
+        stdout_handle,
+        "  Last noted: {} ({})",
+        get_iso8601(row.last_noted_timestamp),
+        format_relative(now, row.last_noted_timestamp)
+    )?;
+    writeln!(stdout_handle, "  Pinned: {}", yes_no(row.pinned))?;
+
+    writeln!(
+        stdout_handle,
+        "Frecency ({} algorithm):",
+        algorithm_name(algorithm)
+    )?;
+    writeln!(
+        stdout_handle,
+        "  Frequency: {frequency:.4} (count {} / highest count {highest_count})",
+        row.noted_count
+    )?;
+    writeln!(
+        stdout_handle,
+        "  Recency: {recency:.4} (last noted {last_noted_age_hours:.1}h ago / oldest note {oldest_last_noted_age_hours:.1}h ago)"
+    )?;
+    if algorithm == FrecencyAlgorithm::Linear {
+        writeln!(stdout_handle, "  Lambda: {lambda} (recency_bias)")?;
+    } else {
+        writeln!(
+            stdout_handle,
+            "  Lambda: {lambda} (recency_bias, not used by the {} algorithm)",
+            algorithm_name(algorithm)
+        )?;
+    }
+    if let Some(dir) = &cwd {
+        writeln!(
+            stdout_handle,
+            "  Current directory boost: x{:.4} (cwd_boost {cwd_boost})",
+            frecency::cwd_multiplier(Path::new(&row.path), dir, cwd_boost)
+        )?;
+    }
+    writeln!(stdout_handle, "  Score: {row_score:.4}")?;
+    writeln!(stdout_handle, "  Rank: {rank}")?;
+
+    writeln!(stdout_handle, "Status:")?;
+    writeln!(stdout_handle, "  Missing: {}", describe_missing(row, now))?;
+    writeln!(
+        stdout_handle,
+        "  Denylisted: {}",
+        describe_denylist(&row.path, &denylist_matcher)
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use core::error::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{Write as _, stdout};
+use tracing::{info, instrument};
+
+use crate::utils;
+use crate::utils::cli::ExportArgs;
+use crate::utils::db;
+use crate::utils::db::TablePathsEntry;
+
+/// Version of the JSON export layout, bumped whenever it changes incompatibly.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// The JSON export layout. CSV exports contain only the `paths` rows.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ExportedDatabase {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub paths: Vec<TablePathsEntry>,
+    #[serde(default)]
+    pub state: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, BTreeSet<String>>,
+}
+
+fn format_export(exported: &ExportedDatabase, format: &str) -> Result<String, Box<dyn Error>> {
+    if format == "csv" {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        for row in &exported.paths {
+            wtr.serialize(row)?;
+        }
+        wtr.flush()?;
+        return Ok(String::from_utf8(wtr.into_inner()?)?);
+    }
+
+    let json_output =
+        serde_json::to_string_pretty(exported).expect("Failed to serialize export to JSON");
+    Ok(format!("{json_output}\n"))
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &ExportArgs) -> Result<(), Box<dyn Error>> {
+    let db_connection = db::open()?;
+    let mut rows = db::get_rows(&db_connection)?;
+    let state = db::get_state(&db_connection)?;
+    let tags = db::get_tags(&db_connection)?;
+    db::close(db_connection)?;
+
+    rows.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    let row_count = rows.len();
+    let exported = ExportedDatabase {
+        format_version: EXPORT_FORMAT_VERSION,
+        exported_at: utils::time::get_iso8601(utils::time::get_timestamp_now()),
+        paths: rows,
+        state,
+        tags,
+    };
+    let output = format_export(&exported, &args.format)?;
+
+    if let Some(output_file) = &args.output {
+        fs::write(output_file, output)?;
+        info!("Exported {row_count} paths to {output_file}");
+    } else {
+        let mut stdout_handle = stdout().lock();
+        write!(stdout_handle, "{output}")?;
+    }
+
+    Ok(())
+}
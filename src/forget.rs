@@ -0,0 +1,106 @@
+use core::error::Error;
+use std::collections::HashSet;
+use std::io::{BufRead as _, IsTerminal as _, Write as _, stderr, stdin, stdout};
+use std::path::Path;
+use tracing::{info, instrument};
+
+use crate::utils::cli::ForgetArgs;
+use crate::utils::config;
+use crate::utils::db;
+use crate::utils::path;
+use crate::utils::search;
+
+fn find_matching_paths(
+    stored_paths: Vec<String>,
+    args: &ForgetArgs,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let exact: HashSet<String> = args
+        .paths
+        .iter()
+        .flat_map(|raw_path| path::candidate_stored_paths(raw_path))
+        .collect();
+    let glob_matcher = search::build_glob_matcher(&args.globs)?;
+
+    Ok(stored_paths
+        .into_iter()
+        .filter(|stored_path| {
+            exact.contains(stored_path)
+                || (!args.keywords.is_empty()
+                    && search::matches_zoxide_algo(stored_path, &args.keywords))
+                || glob_matcher.as_ref().is_some_and(|matcher| {
+                    search::matches_glob(matcher, stored_path, Path::new(stored_path).is_dir())
+                })
+        })
+        .collect())
+}
+
+fn confirm(count: usize) -> Result<bool, Box<dyn Error>> {
+    if !stdin().is_terminal() {
+        return Err(format!(
+            "{count} paths match; refusing to forget them without confirmation (use --yes)"
+        )
+        .into());
+    }
+
+    let mut stderr_handle = stderr().lock();
+    write!(
+        stderr_handle,
+        "Forget {count} paths from the database? [y/N] "
+    )?;
+    stderr_handle.flush()?;
+
+    let mut answer = String::new();
+    stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &ForgetArgs) -> Result<(), Box<dyn Error>> {
+    if args.paths.is_empty() && args.keywords.is_empty() && args.globs.is_empty() {
+        return Err("You must specify some paths, keywords or globs to forget".into());
+    }
+
+    let mut db_connection = db::open()?;
+    let stored_paths = db::get_rows(&db_connection)?
+        .into_iter()
+        .map(|row| row.path)
+        .collect();
+    let mut to_forget = find_matching_paths(stored_paths, args)?;
+
+    to_forget.sort_unstable();
+
+    if args.dry_run {
+        let mut stdout_handle = stdout().lock();
+        for forget_path in &to_forget {
+            writeln!(stdout_handle, "{forget_path}")?;
+        }
+        writeln!(stderr(), "Would forget {} paths", to_forget.len())?;
+        db::close(db_connection)?;
+        return Ok(());
+    }
+
+    if to_forget.is_empty() {
+        db::close(db_connection)?;
+        return Err("no match found".into());
+    }
+
+    let threshold = config::get_forget_confirm_threshold();
+    if !args.yes && to_forget.len() > threshold && !confirm(to_forget.len())? {
+        db::close(db_connection)?;
+        return Err("Aborted, nothing forgotten".into());
+    }
+
+    let tx = db_connection
+        .transaction()
+        .expect("Cannot start DB transaction");
+    db::delete_paths(&tx, &to_forget)?;
+    tx.commit().expect("Cannot commit transaction");
+    db::close(db_connection)?;
+
+    for forget_path in &to_forget {
+        info!("Path {forget_path} forgotten");
+    }
+
+    Ok(())
+}
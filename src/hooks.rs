@@ -7,6 +7,11 @@ use tracing::instrument;
 #[folder = "hooks/"]
 struct Hooks;
 
+/// Returns the text of the hook called `hook_name`, if there is one.
+pub fn get_hook(hook_name: &str) -> Option<String> {
+    Hooks::get(hook_name).map(|content| String::from_utf8_lossy(&content.data).into_owned())
+}
+
 #[instrument(level = "trace")]
 pub fn command(
     hook_name: Option<String>,
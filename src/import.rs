@@ -1,12 +1,18 @@
 use core::error::Error;
 use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{Write as _, stdout};
+use std::path::{Path, PathBuf};
 use tracing::{debug, info, instrument};
 use xdg::BaseDirectories;
 
+use crate::export::{EXPORT_FORMAT_VERSION, ExportedDatabase};
 use crate::utils;
+use crate::utils::cli::ImportArgs;
 use crate::utils::db::TablePathsEntry;
+use crate::utils::merge;
+use crate::utils::merge::{MergeOutcome, MergeSummary};
 use crate::utils::types::NotedCount;
 use crate::utils::types::UnixTimestamp;
 
@@ -37,6 +43,7 @@ fn from_fasd_str(s: &str) -> Result<TablePathsEntry, Box<dyn Error>> {
         path: filename,
         noted_count: score.round() as NotedCount,
         last_noted_timestamp: timestamp,
+        pinned: false,
     })
 }
 
@@ -67,6 +74,7 @@ fn from_whitespace_split_str(s: &str) -> Result<TablePathsEntry, Box<dyn Error>>
         #[allow(clippy::cast_sign_loss, reason = "Round is intentional")]
         noted_count: count.round() as u64,
         last_noted_timestamp: timestamp,
+        pinned: false,
     })
 }
 
@@ -97,6 +105,7 @@ fn insert_into_db(
     entries: Vec<TablePathsEntry>,
 ) -> Result<(), Box<dyn Error>> {
     let tx = conn.transaction().expect("Cannot start DB transaction");
+    let mut imported_any = false;
 
     for entry in entries {
         #[allow(
@@ -115,7 +124,15 @@ fn insert_into_db(
             rusqlite::params![entry.path, entry.noted_count, entry.last_noted_timestamp],
         )
         .map_err(|e| format!("Failed to insert or update entry into database: {e}"))?;
+        utils::db::insert_event(&tx, &entry.path, entry.last_noted_timestamp, None, None)
+            .map_err(|e| format!("Failed to insert event into database: {e}"))?;
         debug!("Imported entry for file {}", entry.path);
+        imported_any = true;
+    }
+
+    // Only the most recent of each imported path's notes has an event
+    if imported_any {
+        utils::db::set_events_complete_since(&tx, utils::time::get_timestamp_now())?;
     }
 
     tx.commit().expect("Cannot commit import transaction");
@@ -174,6 +191,7 @@ fn from_jumper_str(s: &str) -> Result<TablePathsEntry, Box<dyn Error>> {
         path,
         noted_count: count.round() as NotedCount,
         last_noted_timestamp: timestamp,
+        pinned: false,
     })
 }
 
@@ -264,6 +282,123 @@ pub fn run_importers(conn: &mut Connection) {
     process_zoxide_query(conn);
 }
 
+fn read_export_file(
+    file_path: &str,
+    format: Option<&str>,
+) -> Result<ExportedDatabase, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(file_path).map_err(|e| format!("Cannot read {file_path}: {e}"))?;
+
+    let is_csv = format.map_or_else(
+        || {
+            Path::new(file_path)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+        },
+        |f| f == "csv",
+    );
+
+    if is_csv {
+        let mut rdr = csv::Reader::from_reader(contents.as_bytes());
+        let rows = rdr
+            .deserialize()
+            .collect::<Result<Vec<TablePathsEntry>, _>>()
+            .map_err(|e| format!("Invalid CSV export {file_path}: {e}"))?;
+        return Ok(ExportedDatabase {
+            format_version: EXPORT_FORMAT_VERSION,
+            exported_at: String::new(),
+            paths: rows,
+            state: BTreeMap::new(),
+            tags: BTreeMap::new(),
+        });
+    }
+
+    let exported: ExportedDatabase = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid JSON export {file_path}: {e}"))?;
+
+    if exported.format_version > EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "Export format version mismatch: expected at most {EXPORT_FORMAT_VERSION}, found {}.",
+            exported.format_version
+        )
+        .into());
+    }
+
+    Ok(exported)
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &ImportArgs) -> Result<(), Box<dyn Error>> {
+    let exported = read_export_file(&args.file, args.format.as_deref())?;
+
+    let mut db_connection = utils::db::open()?;
+    let local_state = utils::db::get_state(&db_connection)?;
+    let new_state: Vec<(&String, &String)> = exported
+        .state
+        .iter()
+        .filter(|(key, _)| !local_state.contains_key(*key) && !utils::db::is_history_state_key(key))
+        .collect();
+    let local_tags = utils::db::get_tags(&db_connection)?;
+    // Tags are only kept for paths that will be in the database
+    let mut known_paths: HashSet<String> = utils::db::get_rows(&db_connection)?
+        .into_iter()
+        .map(|row| row.path)
+        .collect();
+    known_paths.extend(exported.paths.iter().map(|row| row.path.clone()));
+    let new_tags: Vec<(&String, &String)> = exported
+        .tags
+        .iter()
+        .filter(|(path, _)| known_paths.contains(*path))
+        .flat_map(|(path, tags)| tags.iter().map(move |tag| (path, tag)))
+        .filter(|(path, tag)| {
+            !local_tags
+                .get(*path)
+                .is_some_and(|path_tags| path_tags.contains(*tag))
+        })
+        .collect();
+    let outcomes = merge::plan(&db_connection, &exported.paths, args.strategy)?;
+
+    let summary = MergeSummary::from_outcomes(&outcomes);
+    let mut stdout_handle = stdout().lock();
+
+    if args.dry_run {
+        for outcome in &outcomes {
+            if !matches!(outcome, MergeOutcome::Unchanged) {
+                writeln!(stdout_handle, "{outcome}")?;
+            }
+        }
+        for (key, value) in new_state {
+            writeln!(stdout_handle, "add state {key} = {value}")?;
+        }
+        for (path, tag) in &new_tags {
+            writeln!(stdout_handle, "add tag {tag} to {path}")?;
+        }
+        writeln!(stdout_handle, "Would import: {summary}")?;
+        utils::db::close(db_connection)?;
+        return Ok(());
+    }
+
+    let tx = db_connection
+        .transaction()
+        .expect("Cannot start DB transaction");
+    merge::apply(&tx, &outcomes, &HashMap::new(), args.strategy)?;
+    for (key, value) in new_state {
+        tx.execute(
+            "INSERT INTO state (key, value) VALUES (?1, ?2)",
+            rusqlite::params![key, value],
+        )?;
+    }
+    for (path, tag) in new_tags {
+        utils::db::add_tag(&tx, core::slice::from_ref(path), tag)?;
+    }
+    tx.commit().expect("Cannot commit import transaction");
+    utils::db::close(db_connection)?;
+
+    writeln!(stdout_handle, "Imported: {summary}")?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
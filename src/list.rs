@@ -1,19 +1,29 @@
 use crate::utils::cli::ListArgs;
+use crate::utils::cli::MatchMode;
 use crate::utils::cli::SortOrder;
 use core::error::Error;
+use ignore::gitignore::Gitignore;
+use regex::RegexSet;
 use rusqlite::Connection;
 use std::fs::FileType;
 use std::io::{IsTerminal as _, Write as _, stdout};
+use std::path::{Path, PathBuf};
 use tracing::instrument;
 use tracing::{debug, warn};
 
 use crate::utils;
 use crate::utils::db;
+use crate::utils::frecency;
+use crate::utils::kind;
+use crate::utils::output::OutputFilter;
 use crate::utils::path;
+use crate::utils::picker::{self, PickerItem};
 use crate::utils::query;
-use crate::utils::search::matches_zoxide_algo;
+use crate::utils::search;
+use crate::utils::template::Template;
 use crate::utils::types::Frecency;
 use crate::utils::types::NotedCount;
+use crate::utils::types::UnixTimestamp;
 
 const BREAKING_CHANGE_SORT_WARNING: &str = "\
 ⚠️  NOTICE: 'memy list' now outputs most-frecent-first by default (was least-frecent-first).
@@ -27,48 +37,295 @@ fn check_warning() -> bool {
 #[derive(serde::Serialize)]
 struct PathFrecency {
     path: String,
+    /// As stored in the database, unlike `path` with `--relative`.
+    #[serde(skip)]
+    stored_path: String,
     frecency: Frecency,
     count: NotedCount,
     last_noted: String,
+    #[serde(skip)]
+    last_noted_timestamp: UnixTimestamp,
     #[serde(serialize_with = "crate::utils::serialize_file_type")]
     file_type: FileType,
+    pinned: bool,
+    tags: Vec<String>,
+}
+
+/// CSV can't hold a list in a single field, so tags are joined with `;`.
+#[derive(serde::Serialize)]
+struct CsvPathFrecency<'a> {
+    path: &'a str,
+    frecency: Frecency,
+    count: NotedCount,
+    last_noted: &'a str,
+    #[serde(serialize_with = "crate::utils::serialize_file_type")]
+    file_type: FileType,
+    pinned: bool,
+    tags: String,
+}
+
+impl<'a> From<&'a PathFrecency> for CsvPathFrecency<'a> {
+    fn from(result: &'a PathFrecency) -> Self {
+        Self {
+            path: &result.path,
+            frecency: result.frecency,
+            count: result.count,
+            last_noted: &result.last_noted,
+            file_type: result.file_type,
+            pinned: result.pinned,
+            tags: result.tags.join(";"),
+        }
+    }
+}
+
+/// Returns the directory `--under` or `--repo` limits the list to, if either was given.
+fn find_root(args: &ListArgs) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    if let Some(ref dir) = args.under {
+        let resolved = path::resolve_existing_dir(dir)
+            .ok_or_else(|| format!("--under: {dir} is not a directory"))?;
+        // Paths are stored with symlinks resolved, so the root needs to be too
+        return Ok(Some(resolved.canonicalize()?));
+    }
+
+    if args.repo {
+        let cwd = std::env::current_dir()?;
+        return path::find_git_work_tree(&cwd)
+            .map(Some)
+            .ok_or_else(|| "--repo: the current directory is not inside a git work tree".into());
+    }
+
+    Ok(None)
+}
+
+/// The filters on the text of each path, built once from the arguments.
+struct PathFilters<'a> {
+    root: Option<PathBuf>,
+    keywords: &'a [String],
+    match_mode: MatchMode,
+    glob_matcher: Option<Gitignore>,
+    exclude_glob_matcher: Option<Gitignore>,
+    regex_set: Option<RegexSet>,
+}
+
+impl<'a> PathFilters<'a> {
+    fn new(args: &'a ListArgs) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            root: find_root(args)?,
+            keywords: &args.keywords,
+            match_mode: args
+                .match_mode
+                .unwrap_or_else(utils::config::get_keyword_match),
+            glob_matcher: search::build_glob_matcher(&args.globs)?,
+            exclude_glob_matcher: search::build_glob_matcher(&args.exclude_globs)?,
+            regex_set: search::build_regex_set(&args.regexes)?,
+        })
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if let Some(ref root) = self.root
+            && (Path::new(path) == root || !Path::new(path).starts_with(root))
+        {
+            return false;
+        }
+
+        if let Some(ref matcher) = self.glob_matcher
+            && !search::matches_glob(matcher, path, is_dir)
+        {
+            return false;
+        }
+
+        if let Some(ref matcher) = self.exclude_glob_matcher
+            && search::matches_glob(matcher, path, is_dir)
+        {
+            return false;
+        }
+
+        if let Some(ref regexes) = self.regex_set
+            && !regexes.is_match(path)
+        {
+            return false;
+        }
+
+        self.keywords.is_empty()
+            || match self.match_mode {
+                MatchMode::Zoxide => search::matches_zoxide_algo(path, self.keywords),
+                MatchMode::Fuzzy => search::fuzzy_match_score(path, self.keywords).is_some(),
+            }
+    }
+}
+
+/// The range of last noted times to list, from `--newer-than`, `--older-than` and `--between`.
+struct TimeWindow {
+    /// Inclusive.
+    start: Option<UnixTimestamp>,
+    /// Exclusive.
+    end: Option<UnixTimestamp>,
+}
+
+impl TimeWindow {
+    fn new(args: &ListArgs) -> Result<Self, Box<dyn Error>> {
+        let (between_start, between_end) = match args.between {
+            Some(ref between) => utils::time::parse_between(between)?,
+            None => (None, None),
+        };
+        let newer_than = args
+            .newer_than
+            .as_deref()
+            .map(utils::time::parse_newer_than)
+            .transpose()?;
+        let older_than = args
+            .older_than
+            .as_deref()
+            .map(utils::time::parse_newer_than)
+            .transpose()?;
+
+        Ok(Self {
+            start: newer_than.into_iter().chain(between_start).max(),
+            end: older_than.into_iter().chain(between_end).min(),
+        })
+    }
+
+    fn contains(&self, timestamp: UnixTimestamp) -> bool {
+        self.start.is_none_or(|start| timestamp >= start)
+            && self.end.is_none_or(|end| timestamp < end)
+    }
+}
+
+/// Scales the frecency of each match by its closeness to the current directory and, when fuzzy
+/// matching keywords, by its match score, then re-sorts.
+fn adjust_frecency(
+    matches: &mut [query::MatchEntry],
+    args: &ListArgs,
+    match_mode: MatchMode,
+) -> Result<(), Box<dyn Error>> {
+    let cwd_boost = args.cwd_boost.unwrap_or_else(utils::config::get_cwd_boost);
+    if cwd_boost < 0.0 {
+        return Err("--cwd-boost must not be negative".into());
+    }
+
+    let fuzzy_keywords = match_mode == MatchMode::Fuzzy && !args.keywords.is_empty();
+
+    #[allow(clippy::float_cmp, reason = "1.0 is exactly the 'no boost' default")]
+    if cwd_boost != 1.0 {
+        let cwd = std::env::current_dir()?;
+        for m in matches.iter_mut() {
+            m.frecency *=
+                frecency::cwd_multiplier(Path::new(&m.table_paths_entry.path), &cwd, cwd_boost);
+        }
+    }
+
+    if fuzzy_keywords {
+        for m in matches.iter_mut() {
+            m.frecency *= search::fuzzy_match_score(&m.table_paths_entry.path, &args.keywords)
+                .expect("Filtered to fuzzy matches");
+        }
+    }
+
+    #[allow(clippy::float_cmp, reason = "1.0 is exactly the 'no boost' default")]
+    if cwd_boost != 1.0 || fuzzy_keywords {
+        matches.sort_by(|a, b| a.frecency.total_cmp(&b.frecency));
+    }
+
+    Ok(())
 }
 
 #[instrument(level = "trace")]
 fn calculate(conn: &Connection, args: &ListArgs) -> Result<Vec<PathFrecency>, Box<dyn Error>> {
-    let newer_than_timestamp = if let Some(ref newer_than_str) = args.newer_than {
-        Some(utils::time::parse_newer_than(newer_than_str)?)
-    } else {
+    let time_window = TimeWindow::new(args)?;
+
+    let paths_from_sources = if args.sources.is_empty() {
         None
+    } else {
+        Some(db::get_paths_noted_from(conn, &args.sources)?)
     };
 
-    let matches = query::build_sorted_matches(conn, |row, metadata| {
+    let mut all_tags = db::get_tags(conn)?;
+
+    let path_filters = PathFilters::new(args)?;
+
+    let derive_dirs_decay = (args.derive_dirs || utils::config::get_derive_dirs())
+        .then(utils::config::get_derive_dirs_decay);
+
+    let mut matches = query::build_sorted_matches(conn, derive_dirs_decay, |row, metadata| {
         if (args.files_only && !metadata.is_file()) || (args.directories_only && !metadata.is_dir())
         {
             return query::FilterResult::Exclude;
         }
 
-        if let Some(cutoff_timestamp) = newer_than_timestamp
-            && row.last_noted_timestamp < cutoff_timestamp
+        if !args.extensions.is_empty()
+            && (!metadata.is_file() || !kind::has_extension(&row.path, &args.extensions))
+        {
+            return query::FilterResult::Exclude;
+        }
+
+        if !args.kinds.is_empty()
+            && (!metadata.is_file() || !kind::matches_kind(&row.path, &args.kinds, args.sniff))
+        {
+            return query::FilterResult::Exclude;
+        }
+
+        if !time_window.contains(row.last_noted_timestamp) {
+            return query::FilterResult::Exclude;
+        }
+
+        if let Some(ref paths) = paths_from_sources
+            && !paths.contains(&row.path)
+        {
+            return query::FilterResult::Exclude;
+        }
+
+        if !args.tags.is_empty()
+            && !all_tags
+                .get(&row.path)
+                .is_some_and(|path_tags| args.tags.iter().all(|tag| path_tags.contains(tag)))
         {
             return query::FilterResult::Exclude;
         }
 
-        if !args.keywords.is_empty() && !matches_zoxide_algo(&row.path, &args.keywords) {
+        if !path_filters.matches(&row.path, metadata.is_dir()) {
             return query::FilterResult::Exclude;
         }
 
         query::FilterResult::Include
     })?;
 
+    adjust_frecency(&mut matches, args, path_filters.match_mode)?;
+
+    // Applied after deriving directories (which have a count of 0 unless noted themselves) and
+    // adjusting frecency, so they apply to the counts and scores that are shown
+    matches.retain(|m| {
+        let count = m.table_paths_entry.noted_count;
+        args.min_count.is_none_or(|min| count >= min)
+            && args.max_count.is_none_or(|max| count <= max)
+            && args.min_score.is_none_or(|min| m.frecency >= min)
+    });
+
     let mut to_output: Vec<PathFrecency> = matches
         .into_iter()
         .map(|m| PathFrecency {
-            path: m.table_paths_entry.path,
             frecency: m.frecency,
             count: m.table_paths_entry.noted_count,
             last_noted: utils::time::get_iso8601(m.table_paths_entry.last_noted_timestamp),
+            last_noted_timestamp: m.table_paths_entry.last_noted_timestamp,
             file_type: m.metadata.file_type(),
+            pinned: m.table_paths_entry.pinned,
+            tags: all_tags
+                .remove(&m.table_paths_entry.path)
+                .map(|path_tags| path_tags.into_iter().collect())
+                .unwrap_or_default(),
+            stored_path: m.table_paths_entry.path.clone(),
+            // Whatever is selected from an output filter is used as a path by the caller, whose
+            // directory may not be the root, so it's given the stored path
+            path: match path_filters.root {
+                Some(ref root) if args.relative && !args.output_filter => {
+                    Path::new(&m.table_paths_entry.path)
+                        .strip_prefix(root)
+                        .expect("Filtered to paths below the root")
+                        .to_string_lossy()
+                        .into_owned()
+                }
+                _ => m.table_paths_entry.path,
+            },
         })
         .collect();
 
@@ -81,6 +338,9 @@ fn calculate(conn: &Connection, args: &ListArgs) -> Result<Vec<PathFrecency>, Bo
         to_output.reverse();
     }
 
+    // Stable, so pinned and unpinned paths each stay in the requested order
+    to_output.sort_by_key(|result| !result.pinned);
+
     if let Some(n) = args.effective_limit_results() {
         to_output.truncate(n);
     }
@@ -88,33 +348,253 @@ fn calculate(conn: &Connection, args: &ListArgs) -> Result<Vec<PathFrecency>, Bo
     Ok(to_output)
 }
 
-#[instrument(level = "trace", skip(results, args))]
-fn format_results(results: &[PathFrecency], args: &ListArgs) -> Result<String, Box<dyn Error>> {
+const TEMPLATE_FIELDS: [&str; 7] = [
+    "path",
+    "frecency",
+    "count",
+    "last_noted",
+    "file_type",
+    "pinned",
+    "tags",
+];
+
+fn parse_template(template: &str) -> Result<Template, Box<dyn Error>> {
+    let parsed = Template::parse(template, &TEMPLATE_FIELDS)?;
+
+    for (field, modifier) in parsed.placeholders() {
+        let valid = match (field, modifier) {
+            (_, None) | ("last_noted", Some("relative" | "unix")) => true,
+            ("frecency", Some(precision)) => precision
+                .strip_prefix('.')
+                .is_some_and(|digits| digits.parse::<usize>().is_ok()),
+            _ => false,
+        };
+
+        if !valid {
+            return Err(format!(
+                "Unknown template modifier '{}' for field '{field}'",
+                modifier.unwrap_or_default()
+            )
+            .into());
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn template_value(
+    result: &PathFrecency,
+    field: &str,
+    modifier: Option<&str>,
+    now: UnixTimestamp,
+    use_pretty_paths: bool,
+) -> String {
+    match (field, modifier) {
+        ("path", _) if use_pretty_paths => path::collapse_to_tilde(&result.path),
+        ("path", _) => result.path.clone(),
+        ("frecency", Some(precision)) => {
+            let digits = precision[1..].parse().expect("Checked when parsed");
+            format!("{:.digits$}", result.frecency)
+        }
+        ("frecency", None) => result.frecency.to_string(),
+        ("count", _) => result.count.to_string(),
+        ("last_noted", Some("relative")) => {
+            utils::time::format_relative(now, result.last_noted_timestamp)
+        }
+        ("last_noted", Some("unix")) => result.last_noted_timestamp.to_string(),
+        ("last_noted", _) => result.last_noted.clone(),
+        ("file_type", _) => utils::file_type_name(result.file_type).to_owned(),
+        ("pinned", _) => result.pinned.to_string(),
+        ("tags", _) => result.tags.join(";"),
+        _ => unreachable!("Template fields are checked when parsed"),
+    }
+}
+
+fn format_delimited(
+    results: &[PathFrecency],
+    delimiter: u8,
+    null_separated: bool,
+) -> Result<String, Box<dyn Error>> {
+    let mut builder = csv::WriterBuilder::new();
+    builder.delimiter(delimiter);
+    if null_separated {
+        builder.terminator(csv::Terminator::Any(b'\0'));
+    }
+
+    let mut wtr = builder.from_writer(Vec::new());
+    for result in results {
+        wtr.serialize(CsvPathFrecency::from(result))?;
+    }
+    wtr.flush()?;
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
+#[instrument(level = "trace", skip(results, args, template))]
+fn format_results(
+    results: &[PathFrecency],
+    args: &ListArgs,
+    template: Option<&Template>,
+) -> Result<String, Box<dyn Error>> {
     let use_pretty_paths = args.pretty_paths || utils::config::get_use_pretty_paths();
+    let terminator = if args.null_separated { '\0' } else { '\n' };
+
+    if let Some(parsed) = template {
+        let now = utils::time::get_timestamp_now();
+        return Ok(results
+            .iter()
+            .map(|result| {
+                let mut line = parsed.render(|field, modifier| {
+                    template_value(result, field, modifier, now, use_pretty_paths)
+                });
+                line.push(terminator);
+                line
+            })
+            .collect());
+    }
+
+    // The marker would end up in whatever a script or output filter selects, so only show it to
+    // people reading the list directly
+    let show_pin_marker = stdout().is_terminal() && !args.output_filter;
 
     match args.format.as_str() {
+        "json" if args.null_separated => {
+            Err("--null can't be used with --format json, use --format jsonl instead".into())
+        }
         "json" => {
             let json_output = serde_json::to_string_pretty(&results)
                 .expect("Failed to serialize results to JSON");
             Ok(format!("{}\n", &json_output))
         }
-        "csv" => {
-            let mut wtr = csv::Writer::from_writer(Vec::new());
-            for result in results {
-                wtr.serialize(result)?;
-            }
-            wtr.flush()?;
-            Ok(String::from_utf8(wtr.into_inner()?)?)
+        "jsonl" => Ok(results
+            .iter()
+            .map(|result| {
+                let mut line =
+                    serde_json::to_string(result).expect("Failed to serialize result to JSON");
+                line.push(terminator);
+                line
+            })
+            .collect()),
+        "csv" => format_delimited(results, b',', args.null_separated),
+        "tsv" => format_delimited(results, b'\t', args.null_separated),
+        _ if args.null_separated => Ok(results
+            .iter()
+            .map(|result| {
+                let mut line = if use_pretty_paths {
+                    path::collapse_to_tilde(&result.path)
+                } else {
+                    result.path.clone()
+                };
+                line.push(terminator);
+                line
+            })
+            .collect()),
+        _ if args.long => {
+            let now = utils::time::get_timestamp_now();
+            let rows: Vec<utils::output::LongRow<'_>> = results
+                .iter()
+                .map(|r| utils::output::LongRow {
+                    path: &r.path,
+                    is_dir: r.file_type.is_dir(),
+                    show_pinned_marker: show_pin_marker && r.pinned,
+                    columns: [
+                        format!("{:.2}", r.frecency),
+                        r.count.to_string(),
+                        utils::time::format_relative(now, r.last_noted_timestamp),
+                        utils::file_type_name(r.file_type).to_owned(),
+                    ],
+                })
+                .collect();
+            let max_width = stdout()
+                .is_terminal()
+                .then(utils::graphs::get_terminal_width);
+            Ok(utils::output::format_long_listing(
+                &rows,
+                use_pretty_paths,
+                max_width,
+            ))
         }
         _ => Ok(utils::output::format_paths_colored(
-            results
-                .iter()
-                .map(|r| (r.path.as_str(), r.file_type.is_dir())),
+            results.iter().map(|r| {
+                (
+                    r.path.as_str(),
+                    r.file_type.is_dir(),
+                    show_pin_marker && r.pinned,
+                )
+            }),
             use_pretty_paths,
         )),
     }
 }
 
+fn forget_path(stored_path: &str) -> Result<(), Box<dyn Error>> {
+    let mut db_connection = db::open()?;
+    let tx = db_connection.transaction()?;
+    db::delete_paths(&tx, &[stored_path.to_owned()])?;
+    tx.commit()?;
+    db::close(db_connection)
+}
+
+/// Lets the user pick from `results` with the built-in picker, returning the picked paths one per
+/// line.
+fn pick_with_builtin_picker(
+    results: &[PathFrecency],
+    args: &ListArgs,
+) -> Result<String, Box<dyn Error>> {
+    if !picker::is_available() {
+        return Err(
+            "No output filter command found, and the built-in picker needs a terminal. Set \
+             MEMY_OUTPUT_FILTER environment variable, memy_output_filter in config, or install \
+             fzf/sk/fzy."
+                .into(),
+        );
+    }
+
+    let use_pretty_paths = args.pretty_paths || utils::config::get_use_pretty_paths();
+    let now = utils::time::get_timestamp_now();
+    let items = results
+        .iter()
+        .map(|r| {
+            let mut details = vec![
+                ("Frecency", format!("{:.2}", r.frecency)),
+                ("Count", r.count.to_string()),
+                (
+                    "Last noted",
+                    utils::time::format_relative(now, r.last_noted_timestamp),
+                ),
+                ("Type", utils::file_type_name(r.file_type).to_owned()),
+            ];
+            if r.pinned {
+                details.push(("Pinned", "yes".to_owned()));
+            }
+            if !r.tags.is_empty() {
+                details.push(("Tags", r.tags.join(", ")));
+            }
+
+            PickerItem {
+                display: if use_pretty_paths {
+                    path::collapse_to_tilde(&r.path)
+                } else {
+                    r.path.clone()
+                },
+                path: r.stored_path.clone(),
+                is_dir: r.file_type.is_dir(),
+                details,
+            }
+        })
+        .collect();
+
+    let match_mode = args
+        .match_mode
+        .unwrap_or_else(utils::config::get_keyword_match);
+    let picked = picker::pick(items, match_mode, forget_path)?;
+
+    let mut lines = picked.join("\n");
+    if !lines.is_empty() {
+        lines.push('\n');
+    }
+    Ok(path::expand_tildes_in_multiline_string(&lines))
+}
+
 #[instrument(level = "trace")]
 pub fn command(args: &ListArgs) -> Result<(), Box<dyn Error>> {
     if args.zoxide_compatible && !args.output_filter {
@@ -146,6 +626,8 @@ pub fn command(args: &ListArgs) -> Result<(), Box<dyn Error>> {
         }
     }
 
+    let template = args.template.as_deref().map(parse_template).transpose()?;
+
     let db_connection = db::open()?;
     let results: Vec<PathFrecency> = calculate(&db_connection, args)?;
     db::close(db_connection)?;
@@ -154,7 +636,7 @@ pub fn command(args: &ListArgs) -> Result<(), Box<dyn Error>> {
         return Err("no match found".into());
     }
 
-    let output = format_results(&results, args)?;
+    let output = format_results(&results, args, template.as_ref())?;
 
     let show_warning = check_warning();
     if show_warning {
@@ -162,8 +644,13 @@ pub fn command(args: &ListArgs) -> Result<(), Box<dyn Error>> {
     }
 
     if args.output_filter {
-        let filtered =
-            utils::output::pipe_through_filter(&output, args.output_filter_command.as_deref())?;
+        let filtered = match utils::output::get_output_filter(args.output_filter_command.as_deref())
+        {
+            OutputFilter::Command(filter_cmd) => {
+                utils::output::pipe_through_filter(&output, &filter_cmd)?
+            }
+            OutputFilter::Builtin => pick_with_builtin_picker(&results, args)?,
+        };
         let mut stdout_handle = stdout().lock();
         stdout_handle.write_all(filtered.as_bytes())?;
     } else {
@@ -1,10 +1,20 @@
+mod daemon;
+mod doctor;
+mod explain;
+mod export;
+mod forget;
 mod hooks;
 mod import;
 mod list;
+mod merge;
 mod note;
 mod open;
+mod pin;
 mod stats;
+mod tag;
 mod utils;
+#[cfg(target_os = "linux")]
+mod watch;
 mod z;
 
 use clap::CommandFactory as _;
@@ -31,10 +41,24 @@ fn handle_cli_command(
     match command {
         Commands::Note(note_args) => Ok(note::command(note_args)?),
         Commands::List(list_args) => Ok(list::command(&list_args)?),
+        Commands::Forget(forget_args) => Ok(forget::command(&forget_args)?),
+        Commands::Pin(pin_args) => Ok(pin::command(&pin_args, true)?),
+        Commands::Unpin(unpin_args) => Ok(pin::command(&unpin_args, false)?),
+        Commands::Tag(tag_args) => Ok(tag::command(&tag_args)?),
         Commands::GenerateConfig {} => Ok(utils::config::output_template_config()?),
         Commands::Completions { shell } => Ok(completions(shell)?),
         Commands::Hook { hook_name } => Ok(hooks::command(hook_name)?),
         Commands::Stats(stats_args) => Ok(stats::command(&stats_args)?),
+        Commands::Explain(explain_args) => Ok(explain::command(&explain_args)?),
+        Commands::Export(export_args) => Ok(export::command(&export_args)?),
+        Commands::Import(import_args) => Ok(import::command(&import_args)?),
+        Commands::Merge(merge_args) => Ok(merge::command(&merge_args)?),
+        Commands::Doctor(doctor_args) => Ok(doctor::command(&doctor_args)?),
+        Commands::Daemon {} => Ok(daemon::command()?),
+        #[cfg(target_os = "linux")]
+        Commands::Watch(watch_args) => Ok(watch::command(&watch_args)?),
+        #[cfg(not(target_os = "linux"))]
+        Commands::Watch(_) => Err("memy watch is only supported on Linux".into()),
         Commands::Z(z_args) => Ok(z::command(&z_args)?),
         Commands::Open(open_args) => Ok(open::command(&open_args)?),
     }
@@ -0,0 +1,86 @@
+use core::error::Error;
+use std::io::{Write as _, stdout};
+use std::path::Path;
+use tracing::{debug, instrument};
+
+use crate::utils;
+use crate::utils::cli::MergeArgs;
+use crate::utils::db;
+use crate::utils::merge::{self, MergeOutcome, MergeSummary};
+
+#[instrument(level = "trace")]
+pub fn command(args: &MergeArgs) -> Result<(), Box<dyn Error>> {
+    let other_path = utils::path::expand_tilde_in_path(&args.file);
+    let other_connection = db::open_other(&other_path)?;
+    let (other_rows, missing): (Vec<_>, Vec<_>) = db::get_rows(&other_connection)?
+        .into_iter()
+        .partition(|row| Path::new(&row.path).exists());
+    let other_tags = db::get_tags(&other_connection)?;
+    let other_events = db::get_events(&other_connection)?;
+    let other_events_complete_since = db::get_events_complete_since(&other_connection)?;
+    db::close(other_connection)?;
+
+    for missing_row in &missing {
+        debug!("Skipping {}, missing locally", missing_row.path);
+    }
+
+    let mut db_connection = db::open()?;
+    let local_tags = db::get_tags(&db_connection)?;
+    let new_tags: Vec<(&String, &String)> = other_rows
+        .iter()
+        .filter_map(|row| other_tags.get_key_value(&row.path))
+        .flat_map(|(path, tags)| tags.iter().map(move |tag| (path, tag)))
+        .filter(|(path, tag)| {
+            !local_tags
+                .get(*path)
+                .is_some_and(|path_tags| path_tags.contains(*tag))
+        })
+        .collect();
+    let outcomes = merge::plan(&db_connection, &other_rows, args.strategy)?;
+
+    let summary = MergeSummary::from_outcomes(&outcomes);
+    let skipped = missing.len();
+    let mut stdout_handle = stdout().lock();
+
+    if args.dry_run {
+        for outcome in &outcomes {
+            if !matches!(outcome, MergeOutcome::Unchanged) {
+                writeln!(stdout_handle, "{outcome}")?;
+            }
+        }
+        for (path, tag) in &new_tags {
+            writeln!(stdout_handle, "add tag {tag} to {path}")?;
+        }
+        writeln!(
+            stdout_handle,
+            "Would merge: {summary}, {skipped} skipped (missing locally)"
+        )?;
+        db::close(db_connection)?;
+        return Ok(());
+    }
+
+    let tx = db_connection
+        .transaction()
+        .expect("Cannot start DB transaction");
+    merge::apply(&tx, &outcomes, &other_events, args.strategy)?;
+    // The other database's events are only as complete as its own history
+    if let Some(since) = other_events_complete_since
+        && outcomes
+            .iter()
+            .any(|outcome| !matches!(outcome, MergeOutcome::Unchanged))
+    {
+        db::set_events_complete_since(&tx, since)?;
+    }
+    for (path, tag) in new_tags {
+        db::add_tag(&tx, core::slice::from_ref(path), tag)?;
+    }
+    tx.commit().expect("Cannot commit merge transaction");
+    db::close(db_connection)?;
+
+    writeln!(
+        stdout_handle,
+        "Merged: {summary}, {skipped} skipped (missing locally)"
+    )?;
+
+    Ok(())
+}
@@ -3,12 +3,14 @@ use ignore::gitignore::Gitignore;
 use rayon::prelude::*;
 use rusqlite::Transaction;
 use rusqlite::params;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::instrument;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
+use crate::daemon::{self, Forwarded};
 use crate::utils;
 use crate::utils::cli;
 use crate::utils::config;
@@ -25,41 +27,73 @@ fn normalize_path_if_needed(path: Cow<'_, Path>) -> std::io::Result<Cow<'_, Path
     }
 }
 
-/// Returns `Ok(None)` when the path should be silently skipped, `Ok(Some(path))`
-/// when it should be inserted, or `Err` on an unexpected I/O failure.
-fn preprocess_path(raw_path: &str, matcher: &Gitignore) -> std::io::Result<Option<PathBuf>> {
+/// Why a path given to `memy note` was skipped.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Skip {
+    Missing(String),
+    Denied(String),
+}
+
+impl Skip {
+    /// Warns about the skipped path, if the config asks for it.
+    pub fn warn(&self) {
+        match self {
+            Self::Missing(raw_path) => {
+                if config::get_missing_files_warn_on_note() {
+                    warn!("Path {raw_path} does not exist.");
+                }
+            }
+            Self::Denied(path) => {
+                if config::get_denied_files_warn_on_note() {
+                    warn!("Path {path} denied by denylist pattern.");
+                }
+            }
+        }
+    }
+}
+
+/// Returns `Ok(Ok(path))` when the path should be inserted, `Ok(Err(skip))` when it should be
+/// skipped, or `Err` on an unexpected I/O failure.
+pub fn preprocess_path(
+    raw_path: &str,
+    matcher: &Gitignore,
+) -> std::io::Result<Result<PathBuf, Skip>> {
     let path = utils::path::expand_tilde_in_path(raw_path);
 
     if !path.exists() {
-        if config::get_missing_files_warn_on_note() {
-            warn!("Path {raw_path} does not exist.");
-        }
-        return Ok(None);
+        return Ok(Err(Skip::Missing(raw_path.to_owned())));
     }
 
     let clean_path = normalize_path_if_needed(path)?;
 
     if let ignore::Match::Ignore(_) = matcher.matched_path_or_any_parents(&clean_path, false) {
-        if config::get_denied_files_warn_on_note() {
-            warn!("Path {} denied by denylist pattern.", clean_path.display());
-        }
-        return Ok(None);
+        return Ok(Err(Skip::Denied(clean_path.display().to_string())));
     }
 
-    Ok(Some(clean_path.into_owned()))
+    Ok(Ok(clean_path.into_owned()))
 }
 
-fn insert_path(tx: &Transaction, path: &Path, now: UnixTimestamp) {
+pub fn insert_path(
+    tx: &Transaction,
+    path: &Path,
+    now: UnixTimestamp,
+    source: Option<&str>,
+    cwd: Option<&str>,
+) -> rusqlite::Result<()> {
+    let path_str = path.to_string_lossy();
+
     tx.execute(
         "INSERT INTO paths (path, noted_count, last_noted_timestamp) VALUES (?1, 1, ?2) \
             ON CONFLICT(path) DO UPDATE SET \
                 noted_count = noted_count + 1, \
                 last_noted_timestamp = excluded.last_noted_timestamp",
-        params![path.to_string_lossy(), now],
-    )
-    .expect("Insert failed");
+        params![path_str, now],
+    )?;
+
+    db::insert_event(tx, &path_str, now, source, cwd)?;
 
     info!("Path {} noted", path.display());
+    Ok(())
 }
 
 #[instrument(level = "trace")]
@@ -68,22 +102,50 @@ pub fn command(note_args: cli::NoteArgs) -> Result<(), Box<dyn Error>> {
         return Err("You must specify some paths to note".into());
     }
 
+    let now = utils::time::get_timestamp_now();
+    let cwd = std::env::current_dir()
+        .ok()
+        .map(|dir| dir.to_string_lossy().into_owned());
+
+    match daemon::forward_note(
+        &note_args.paths,
+        note_args.source.as_deref(),
+        cwd.as_deref(),
+        now,
+    )? {
+        Forwarded::Noted(skipped) => {
+            skipped.iter().for_each(Skip::warn);
+            return Ok(());
+        }
+        Forwarded::NotNoted(reason) => {
+            debug!("Noting directly, not through the memy daemon: {reason}");
+        }
+    }
+
     let matcher = config::get_denylist_matcher();
 
-    let preprocessed: Vec<Option<PathBuf>> = note_args
+    let preprocessed: Vec<Result<PathBuf, Skip>> = note_args
         .paths
         .into_par_iter()
         .map(|raw_path| preprocess_path(&raw_path, &matcher))
         .collect::<Result<_, _>>()?;
 
-    let now = utils::time::get_timestamp_now();
     let mut db_connection = db::open().expect("Could not open memy database");
     let tx = db_connection
         .transaction()
         .expect("Cannot start DB transaction");
 
-    for clean_path in preprocessed.into_iter().flatten() {
-        insert_path(&tx, &clean_path, now);
+    for outcome in preprocessed {
+        match outcome {
+            Ok(clean_path) => insert_path(
+                &tx,
+                &clean_path,
+                now,
+                note_args.source.as_deref(),
+                cwd.as_deref(),
+            )?,
+            Err(skip) => skip.warn(),
+        }
     }
 
     tx.commit().expect("Cannot commit transaction");
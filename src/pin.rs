@@ -0,0 +1,52 @@
+use core::error::Error;
+use std::io::{Write as _, stdout};
+use tracing::{debug, info, instrument};
+
+use crate::utils::cli::PinArgs;
+use crate::utils::db;
+
+#[instrument(level = "trace")]
+pub fn command(args: &PinArgs, pinned: bool) -> Result<(), Box<dyn Error>> {
+    let db_connection = db::open()?;
+    let rows = db::get_rows(&db_connection)?;
+
+    if args.paths.is_empty() {
+        db::close(db_connection)?;
+
+        if !pinned {
+            return Err("You must specify some paths to unpin".into());
+        }
+
+        let mut pinned_paths: Vec<String> = rows
+            .into_iter()
+            .filter(|row| row.pinned)
+            .map(|row| row.path)
+            .collect();
+        pinned_paths.sort_unstable();
+
+        let mut stdout_handle = stdout().lock();
+        for pinned_path in &pinned_paths {
+            writeln!(stdout_handle, "{pinned_path}")?;
+        }
+        return Ok(());
+    }
+
+    let to_update = match db::resolve_stored_paths(&db_connection, &args.paths) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+            db::close(db_connection)?;
+            return Err(err);
+        }
+    };
+
+    let updated = db::set_pinned(&db_connection, &to_update, pinned)?;
+    debug!("{updated} paths updated");
+    db::close(db_connection)?;
+
+    let action = if pinned { "pinned" } else { "unpinned" };
+    for updated_path in &to_update {
+        info!("Path {updated_path} {action}");
+    }
+
+    Ok(())
+}
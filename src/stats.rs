@@ -1,21 +1,35 @@
-use crate::utils::db::FromRow as _;
 use chrono::{Datelike as _, Local, TimeZone as _, Timelike as _};
+use clap::ValueEnum as _;
+use core::cmp::Reverse;
 use core::error::Error;
 use rusqlite::Connection;
-use rusqlite::{OptionalExtension as _, params};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::fs::metadata;
 use std::io::{Write as _, stdout};
+use std::path::{Path, PathBuf};
 use tracing::instrument;
 
 use crate::utils;
 use crate::utils::cli;
+use crate::utils::cli::StatsWeight;
 use crate::utils::db;
 use crate::utils::db::TablePathsEntry;
-use crate::utils::graphs::{COL_WIDTH, get_terminal_width, render_bar_chart, render_column_chart};
-use crate::utils::time::get_datetime_local;
+use crate::utils::frecency;
+use crate::utils::graphs::{
+    COL_WIDTH, HeatmapWeek, get_terminal_width, render_bar_chart, render_column_chart,
+    render_heatmap, render_weighted_bar_chart,
+};
+use crate::utils::kind;
+use crate::utils::path::{collapse_to_tilde, find_git_work_tree};
+use crate::utils::time::{
+    get_datetime_local, get_iso8601, get_timestamp_now, parse_newer_than, timestamp_age_hours,
+};
 use crate::utils::types::{NotedCount, UnixTimestamp};
 
+/// What the extension breakdown calls files without an extension.
+const NO_EXTENSION: &str = "(none)";
+
 #[derive(serde::Serialize)]
 pub struct StatsOutput {
     pub total_paths: usize,
@@ -25,10 +39,161 @@ pub struct StatsOutput {
     pub oldest_note: Option<TablePathsEntry>,
     pub newest_note: Option<TablePathsEntry>,
     pub highest_count: Option<TablePathsEntry>,
+    pub notes_by_source: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<Comparison>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<Breakdown>,
     #[serde(skip)]
     pub all_timestamps: Vec<UnixTimestamp>,
     #[serde(skip)]
     pub all_noted_counts: Vec<NotedCount>,
+    #[serde(skip)]
+    pub note_timestamps: Vec<UnixTimestamp>,
+}
+
+/// The notes `memy stats` covers, from `start` (inclusive) to `end` (exclusive); either may be
+/// open.
+#[derive(Clone, Copy)]
+struct Window {
+    start: Option<UnixTimestamp>,
+    end: Option<UnixTimestamp>,
+}
+
+impl Window {
+    fn new(args: &cli::StatsArgs) -> Result<Self, Box<dyn Error>> {
+        let parse = |time: Option<&str>| time.map(parse_newer_than).transpose();
+
+        Ok(Self {
+            start: parse(args.newer_than.as_deref())?,
+            end: parse(args.older_than.as_deref())?,
+        })
+    }
+
+    const fn is_all_time(self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    const fn bounds(self) -> (UnixTimestamp, UnixTimestamp) {
+        (
+            match self.start {
+                Some(start) => start,
+                None => UnixTimestamp::MIN,
+            },
+            match self.end {
+                Some(end) => end,
+                None => UnixTimestamp::MAX,
+            },
+        )
+    }
+
+    /// The window of the same length that ends where this one starts.
+    fn previous(self, now: UnixTimestamp) -> Result<Self, Box<dyn Error>> {
+        let start = self
+            .start
+            .ok_or("--compare needs --newer-than, so the window has a length")?;
+        let length = self.end.unwrap_or(now) - start;
+
+        Ok(Self {
+            start: Some(start - length),
+            end: Some(start),
+        })
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct WindowSummary {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub paths_noted: usize,
+    /// Paths noted for the first time in the window.
+    pub new_paths: usize,
+    pub total_notes: NotedCount,
+    /// If the window starts before every note was recorded (before upgrading from an older memy,
+    /// or importing or merging in other paths), when the history is complete from; the counts
+    /// above are then too low.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_before: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Mover {
+    pub path: String,
+    pub notes: NotedCount,
+    pub previous_notes: NotedCount,
+    pub change: i64,
+}
+
+#[derive(serde::Serialize)]
+pub struct Comparison {
+    pub previous: WindowSummary,
+    pub movers: Vec<Mover>,
+}
+
+#[derive(serde::Serialize)]
+pub struct BreakdownEntry {
+    pub name: String,
+    pub paths: usize,
+    pub weight: f64,
+}
+
+/// Where activity is concentrated: the top directories paths are in, their extensions and kinds,
+/// and the git repositories they belong to, each weighted by noted count or frecency.
+#[derive(serde::Serialize)]
+pub struct Breakdown {
+    #[serde(serialize_with = "serialize_weight")]
+    pub weight: StatsWeight,
+    pub directories: Vec<BreakdownEntry>,
+    pub extensions: Vec<BreakdownEntry>,
+    pub kinds: Vec<BreakdownEntry>,
+    pub repositories: Vec<BreakdownEntry>,
+}
+
+const fn weight_name(weight: StatsWeight) -> &'static str {
+    match weight {
+        StatsWeight::Count => "count",
+        StatsWeight::Frecency => "frecency",
+    }
+}
+
+#[allow(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "serialize_with passes fields by reference"
+)]
+fn serialize_weight<S>(weight: &StatsWeight, s: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    s.serialize_str(weight_name(*weight))
+}
+
+#[derive(Default)]
+struct Tally(BTreeMap<String, (usize, f64)>);
+
+impl Tally {
+    fn add(&mut self, name: String, weight: f64) {
+        let entry = self.0.entry(name).or_insert((0, 0.0));
+        entry.0 += 1;
+        entry.1 += weight;
+    }
+
+    /// The `top` heaviest entries, heaviest first, with ties in name order.
+    fn into_top(self, top: usize) -> Vec<BreakdownEntry> {
+        let mut entries: Vec<BreakdownEntry> = self
+            .0
+            .into_iter()
+            .map(|(name, (paths, weight))| BreakdownEntry {
+                name,
+                paths,
+                weight,
+            })
+            .collect();
+        entries.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+        entries.truncate(top);
+        entries
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -92,28 +257,30 @@ impl TimeGranularity {
     }
 
     fn next_bucket(self, timestamp: UnixTimestamp) -> UnixTimestamp {
+        // Days and weeks use calendar-day arithmetic so that DST transitions (±1 hour) don't
+        // cause the iteration to miss a bucket that starting_timestamp() computed via the same
+        // calendar-aware approach.
+        let days_later = |days: u64| {
+            let dt = get_datetime_local(timestamp);
+            let next_date = dt.date_naive() + chrono::Days::new(days);
+            Local
+                .with_ymd_and_hms(
+                    next_date.year(),
+                    next_date.month(),
+                    next_date.day(),
+                    0,
+                    0,
+                    0,
+                )
+                .single()
+                .expect("valid date")
+                .timestamp()
+        };
+
         match self {
             Self::Hour => timestamp + 3_600,
-            Self::Day => timestamp + 86_400,
-            Self::Week => {
-                // Use calendar-day arithmetic so that DST transitions (±1 hour) don't
-                // cause the iteration to miss a bucket that starting_timestamp() computed
-                // via the same calendar-aware approach.
-                let dt = get_datetime_local(timestamp);
-                let next_date = dt.date_naive() + chrono::Days::new(7);
-                Local
-                    .with_ymd_and_hms(
-                        next_date.year(),
-                        next_date.month(),
-                        next_date.day(),
-                        0,
-                        0,
-                        0,
-                    )
-                    .single()
-                    .expect("valid date")
-                    .timestamp()
-            }
+            Self::Day => days_later(1),
+            Self::Week => days_later(7),
             Self::Month => {
                 let dt = get_datetime_local(timestamp);
                 let (year, month) = if dt.month() == 12 {
@@ -154,34 +321,35 @@ impl TimeGranularity {
     }
 }
 
-fn query_path_limit_timestamp(
-    conn: &Connection,
-    order: &str,
-) -> Result<Option<TablePathsEntry>, Box<dyn Error>> {
-    let result = conn
-        .query_row(
-            &format!("SELECT * FROM paths ORDER BY {order} LIMIT 1"),
-            params![],
-            TablePathsEntry::from_row,
-        )
-        .optional()?;
-    Ok(result)
-}
-
 #[instrument(level = "trace")]
 pub fn get(conn: &Connection) -> Result<StatsOutput, Box<dyn Error>> {
-    let oldest_note = query_path_limit_timestamp(conn, "last_noted_timestamp ASC")?;
-    let newest_note = query_path_limit_timestamp(conn, "last_noted_timestamp DESC")?;
-    let highest_count = query_path_limit_timestamp(conn, "noted_count DESC")?;
-
     let rows = db::get_rows(conn)?;
+    let notes_by_source =
+        db::get_note_counts_by_source(conn, UnixTimestamp::MIN, UnixTimestamp::MAX)?;
+    Ok(summarise(&rows, notes_by_source))
+}
+
+fn summarise(rows: &[TablePathsEntry], notes_by_source: BTreeMap<String, usize>) -> StatsOutput {
+    let oldest_note = rows
+        .iter()
+        .min_by_key(|row| row.last_noted_timestamp)
+        .cloned();
+    let newest_note = rows
+        .iter()
+        .min_by_key(|row| Reverse(row.last_noted_timestamp))
+        .cloned();
+    let highest_count = rows
+        .iter()
+        .min_by_key(|row| Reverse(row.noted_count))
+        .cloned();
+
     let mut files_count = 0_usize;
     let mut dirs_count = 0_usize;
     let mut missing_count = 0_usize;
     let mut all_timestamps = Vec::with_capacity(rows.len());
     let mut all_noted_counts = Vec::with_capacity(rows.len());
 
-    for row in &rows {
+    for row in rows {
         all_timestamps.push(row.last_noted_timestamp);
         all_noted_counts.push(row.noted_count);
         match std::fs::metadata(&row.path) {
@@ -191,7 +359,7 @@ pub fn get(conn: &Connection) -> Result<StatsOutput, Box<dyn Error>> {
         }
     }
 
-    Ok(StatsOutput {
+    StatsOutput {
         total_paths: rows.len(),
         files_count,
         dirs_count,
@@ -199,11 +367,264 @@ pub fn get(conn: &Connection) -> Result<StatsOutput, Box<dyn Error>> {
         oldest_note,
         newest_note,
         highest_count,
+        notes_by_source,
+        window: None,
+        comparison: None,
+        breakdown: None,
         all_timestamps,
         all_noted_counts,
+        note_timestamps: vec![],
+    }
+}
+
+/// Summarises the notes made in `window`, whose `rows` have already been fetched.
+fn summarise_window(
+    conn: &Connection,
+    window: Window,
+    rows: &[TablePathsEntry],
+) -> Result<WindowSummary, Box<dyn Error>> {
+    let (start, end) = window.bounds();
+    let complete_since = db::get_events_complete_since(conn)?;
+
+    Ok(WindowSummary {
+        start: window.start.map(get_iso8601),
+        end: window.end.map(get_iso8601),
+        paths_noted: rows.len(),
+        new_paths: db::count_paths_first_noted_between(conn, start, end)?,
+        total_notes: rows.iter().map(|row| row.noted_count).sum(),
+        partial_before: complete_since
+            .filter(|&since| start < since)
+            .map(get_iso8601),
+    })
+}
+
+/// Compares the notes in `window`, whose `rows` have already been fetched, with those in
+/// `previous`, listing up to `top` paths whose noted count changed the most.
+fn compare_windows(
+    conn: &Connection,
+    rows: &[TablePathsEntry],
+    previous: Window,
+    top: usize,
+) -> Result<Comparison, Box<dyn Error>> {
+    let (start, end) = previous.bounds();
+    let previous_rows = db::get_rows_noted_between(conn, start, end)?;
+
+    let mut counts: BTreeMap<&str, (NotedCount, NotedCount)> = BTreeMap::new();
+    for row in rows {
+        counts.entry(&row.path).or_default().0 = row.noted_count;
+    }
+    for row in &previous_rows {
+        counts.entry(&row.path).or_default().1 = row.noted_count;
+    }
+
+    let mut movers: Vec<Mover> = counts
+        .into_iter()
+        .filter(|(_, (notes, previous_notes))| notes != previous_notes)
+        .map(|(path, (notes, previous_notes))| Mover {
+            path: path.to_owned(),
+            notes,
+            previous_notes,
+            change: notes.cast_signed() - previous_notes.cast_signed(),
+        })
+        .collect();
+    // Stable, so movers by the same amount stay in path order
+    movers.sort_by_key(|mover| Reverse(mover.change.unsigned_abs()));
+    movers.truncate(top);
+
+    Ok(Comparison {
+        previous: summarise_window(conn, previous, &previous_rows)?,
+        movers,
     })
 }
 
+/// The directories `path` counts towards: its parent, and the parent's ancestors up to but not
+/// including `home` or the root, which nearly every path would count towards.
+fn counted_directories<'a>(
+    path: &'a Path,
+    home: Option<&'a Path>,
+) -> impl Iterator<Item = &'a Path> {
+    let is_below_top = move |dir: &Path| dir.parent().is_some() && Some(dir) != home;
+    let parent = path.parent().unwrap_or(path);
+    let ancestors = is_below_top(parent).then(|| parent.ancestors().skip(1));
+    core::iter::once(parent).chain(
+        ancestors
+            .into_iter()
+            .flatten()
+            .take_while(move |dir| is_below_top(dir)),
+    )
+}
+
+/// Builds the [`Breakdown`] of the `rows` whose paths still exist.
+fn get_breakdown(rows: &[TablePathsEntry], top: usize, weight: StatsWeight) -> Breakdown {
+    let now = get_timestamp_now();
+    let highest_count = rows.iter().map(|row| row.noted_count).max().unwrap_or(0);
+    let oldest_last_noted_age_hours = rows
+        .iter()
+        .map(|row| row.last_noted_timestamp)
+        .min()
+        .map_or(0.0, |timestamp| timestamp_age_hours(now, timestamp));
+    let scorer = frecency::get_scorer();
+    let home = std::env::home_dir();
+
+    let mut directories = Tally::default();
+    let mut extensions = Tally::default();
+    let mut kinds = Tally::default();
+    let mut repositories = Tally::default();
+    // Many paths share a directory, so only look for each directory's work tree once
+    let mut work_trees: HashMap<PathBuf, Option<PathBuf>> = HashMap::new();
+
+    for row in rows {
+        let Ok(meta) = metadata(&row.path) else {
+            continue;
+        };
+        let path = Path::new(&row.path);
+        let parent = path.parent().unwrap_or(path);
+        let row_weight = match weight {
+            StatsWeight::Count => row.noted_count as f64,
+            StatsWeight::Frecency => scorer.score(&frecency::FrecencyInput {
+                count: row.noted_count,
+                last_noted_age_hours: timestamp_age_hours(now, row.last_noted_timestamp),
+                highest_count,
+                oldest_last_noted_age_hours,
+            }),
+        };
+
+        for dir in counted_directories(path, home.as_deref()) {
+            directories.add(dir.to_string_lossy().into_owned(), row_weight);
+        }
+
+        if meta.is_file() {
+            let extension = path.extension().map_or_else(
+                || NO_EXTENSION.to_owned(),
+                |ext| ext.to_string_lossy().to_lowercase(),
+            );
+            extensions.add(extension, row_weight);
+
+            if let Some(file_kind) = kind::kind_from_extension(&row.path)
+                && let Some(value) = file_kind.to_possible_value()
+            {
+                kinds.add(value.get_name().to_owned(), row_weight);
+            }
+        }
+
+        let search_from = if meta.is_dir() { path } else { parent };
+        if let Some(work_tree) = work_trees
+            .entry(search_from.to_path_buf())
+            .or_insert_with(|| find_git_work_tree(search_from))
+        {
+            repositories.add(work_tree.to_string_lossy().into_owned(), row_weight);
+        }
+    }
+
+    Breakdown {
+        weight,
+        directories: directories.into_top(top),
+        extensions: extensions.into_top(top),
+        kinds: kinds.into_top(top),
+        repositories: repositories.into_top(top),
+    }
+}
+
+/// Renders one part of a [`Breakdown`] as a bar chart, with paths shortened to `~`.
+fn render_breakdown_chart(
+    title: &str,
+    entries: &[BreakdownEntry],
+    weight: StatsWeight,
+    paths: bool,
+    terminal_width: usize,
+) -> String {
+    let rows: Vec<(String, f64)> = entries
+        .iter()
+        .map(|entry| {
+            let label = if paths {
+                collapse_to_tilde(&entry.name)
+            } else {
+                entry.name.clone()
+            };
+            let value = match weight {
+                StatsWeight::Count => entry.weight,
+                StatsWeight::Frecency => (entry.weight * 100.0).round() / 100.0,
+            };
+            (label, value)
+        })
+        .collect();
+
+    render_weighted_bar_chart(
+        &format!("{title} (by {})", weight_name(weight)),
+        &rows,
+        terminal_width,
+    )
+}
+
+fn describe_window(window: &WindowSummary) -> String {
+    let partial = window
+        .partial_before
+        .as_ref()
+        .map_or_else(String::new, |since| {
+            format!(" (partial: not every note before {since} was recorded)")
+        });
+    format!(
+        "{} to {}{partial}",
+        window.start.as_deref().unwrap_or("the first note"),
+        window.end.as_deref().unwrap_or("now")
+    )
+}
+
+/// Writes the summary of the window `memy stats` covers and, if comparing, how it changed from the
+/// previous window.
+fn write_window(
+    out: &mut impl std::io::Write,
+    window: &WindowSummary,
+    comparison: Option<&Comparison>,
+) -> std::io::Result<()> {
+    writeln!(out, "Window: {}", describe_window(window))?;
+
+    let change = |current: u64, previous: u64| {
+        format!(" ({:+})", current.cast_signed() - previous.cast_signed())
+    };
+    let previous = comparison.map(|c| &c.previous);
+    for (label, current, previous_value) in [
+        (
+            "Paths Noted",
+            window.paths_noted as u64,
+            previous.map(|p| p.paths_noted as u64),
+        ),
+        (
+            "New Paths",
+            window.new_paths as u64,
+            previous.map(|p| p.new_paths as u64),
+        ),
+        (
+            "Total Notes",
+            window.total_notes,
+            previous.map(|p| p.total_notes),
+        ),
+    ] {
+        let shown_change = previous_value.map_or_else(String::new, |p| change(current, p));
+        writeln!(out, "  {label}: {current}{shown_change}")?;
+    }
+
+    if let Some(compared) = comparison {
+        writeln!(
+            out,
+            "Compared With: {}",
+            describe_window(&compared.previous)
+        )?;
+        if !compared.movers.is_empty() {
+            writeln!(out, "Biggest Movers:")?;
+            for mover in &compared.movers {
+                writeln!(
+                    out,
+                    "  {:+} {} ({} notes, previously {})",
+                    mover.change, mover.path, mover.notes, mover.previous_notes
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn build_histogram(counts: &[NotedCount]) -> Vec<(String, usize)> {
     if counts.is_empty() {
         return vec![];
@@ -303,12 +724,87 @@ fn build_time_chart(
     (title, entries)
 }
 
-#[instrument(level = "trace")]
-pub fn command(args: &cli::StatsArgs) -> Result<(), Box<dyn Error>> {
+/// Gathers the stats for the window, breakdowns and comparison `args` ask for.
+fn collect(args: &cli::StatsArgs) -> Result<StatsOutput, Box<dyn Error>> {
+    let window = Window::new(args)?;
+    let (start, end) = window.bounds();
+
     let db_connection = db::open()?;
-    let stats = get(&db_connection)?;
+    let rows = if window.is_all_time() {
+        db::get_rows(&db_connection)?
+    } else {
+        db::get_rows_noted_between(&db_connection, start, end)?
+    };
+    let notes_by_source = db::get_note_counts_by_source(&db_connection, start, end)?;
+
+    let mut stats = summarise(&rows, notes_by_source);
+    // Checks every path on disk and looks for its git work tree, so is skipped if nothing of it
+    // would be shown
+    if args.top > 0 {
+        stats.breakdown = Some(get_breakdown(&rows, args.top, args.weight));
+    }
+    if args.heatmap {
+        stats.note_timestamps = db::get_note_timestamps(&db_connection, start, end)?;
+    }
+    if !window.is_all_time() {
+        stats.window = Some(summarise_window(&db_connection, window, &rows)?);
+    }
+    if args.compare.is_some() {
+        let previous = window.previous(get_timestamp_now())?;
+        stats.comparison = Some(compare_windows(&db_connection, &rows, previous, args.top)?);
+    }
     db::close(db_connection)?;
 
+    Ok(stats)
+}
+
+/// Counts `timestamps` by day and lays the days out a week per column, from the week of the
+/// earliest up to the week of `now`.
+fn build_heatmap(timestamps: &[UnixTimestamp], now: UnixTimestamp) -> Vec<HeatmapWeek> {
+    let Some(&min_ts) = timestamps.iter().min() else {
+        return vec![];
+    };
+
+    let mut day_counts: BTreeMap<UnixTimestamp, usize> = BTreeMap::new();
+    for &ts in timestamps {
+        *day_counts
+            .entry(TimeGranularity::Day.starting_timestamp(ts))
+            .or_insert(0) += 1;
+    }
+
+    let today = TimeGranularity::Day.starting_timestamp(now);
+    let now_week = TimeGranularity::Week.starting_timestamp(now);
+    let mut weeks = Vec::new();
+    let mut previous_month = None;
+    let mut week_start = TimeGranularity::Week.starting_timestamp(min_ts);
+    loop {
+        let mut days = [None; 7];
+        let mut day = week_start;
+        for slot in &mut days {
+            if day <= today {
+                *slot = Some(*day_counts.get(&day).unwrap_or(&0));
+            }
+            day = TimeGranularity::Day.next_bucket(day);
+        }
+
+        let dt = get_datetime_local(week_start);
+        let label = (previous_month != Some(dt.month())).then(|| dt.format("%b").to_string());
+        previous_month = Some(dt.month());
+        weeks.push(HeatmapWeek { label, days });
+
+        if week_start >= now_week {
+            break;
+        }
+        week_start = TimeGranularity::Week.next_bucket(week_start);
+    }
+
+    weeks
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &cli::StatsArgs) -> Result<(), Box<dyn Error>> {
+    let stats = collect(args)?;
+
     let mut stdout_handle = stdout().lock();
 
     if args.format.as_str() == "json" {
@@ -316,6 +812,11 @@ pub fn command(args: &cli::StatsArgs) -> Result<(), Box<dyn Error>> {
             serde_json::to_string_pretty(&stats).expect("Failed to serialize stats to JSON");
         writeln!(stdout_handle, "{json_str}")?;
     } else {
+        if let Some(window) = &stats.window {
+            write_window(&mut stdout_handle, window, stats.comparison.as_ref())?;
+            writeln!(stdout_handle)?;
+        }
+
         writeln!(stdout_handle, "Total Paths: {}", stats.total_paths)?;
         writeln!(stdout_handle, "  Files: {}", stats.files_count)?;
         writeln!(stdout_handle, "  Directories: {}", stats.dirs_count)?;
@@ -360,7 +861,27 @@ pub fn command(args: &cli::StatsArgs) -> Result<(), Box<dyn Error>> {
             }
         }
 
-        if stats.all_timestamps.len() >= 2 {
+        if !stats.notes_by_source.is_empty() {
+            let mut by_source: Vec<(String, usize)> = stats
+                .notes_by_source
+                .iter()
+                .map(|(source, count)| (source.clone(), *count))
+                .collect();
+            by_source.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+            writeln!(stdout_handle)?;
+            let chart = render_bar_chart("Notes by Source", &by_source, terminal_width);
+            write!(stdout_handle, "{chart}")?;
+        }
+
+        if args.heatmap {
+            let weeks = build_heatmap(&stats.note_timestamps, Local::now().timestamp());
+            if !weeks.is_empty() {
+                writeln!(stdout_handle)?;
+                let chart = render_heatmap("Notes by Day", &weeks, terminal_width);
+                write!(stdout_handle, "{chart}")?;
+            }
+        } else if stats.all_timestamps.len() >= 2 {
             let (time_title, time_entries) = build_time_chart(
                 &stats.all_timestamps,
                 Local::now().timestamp(),
@@ -372,11 +893,29 @@ pub fn command(args: &cli::StatsArgs) -> Result<(), Box<dyn Error>> {
                 write!(stdout_handle, "{chart}")?;
             }
         }
+
+        if let Some(breakdown) = &stats.breakdown {
+            for (title, entries, paths) in [
+                ("Top Directories", &breakdown.directories, true),
+                ("Top Extensions", &breakdown.extensions, false),
+                ("Top Kinds", &breakdown.kinds, false),
+                ("Top Repositories", &breakdown.repositories, true),
+            ] {
+                if entries.is_empty() {
+                    continue;
+                }
+                writeln!(stdout_handle)?;
+                let chart =
+                    render_breakdown_chart(title, entries, breakdown.weight, paths, terminal_width);
+                write!(stdout_handle, "{chart}")?;
+            }
+        }
     }
 
     Ok(())
 }
 
+#[allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -419,6 +958,96 @@ mod tests {
         assert_eq!(result[1].0, "4-7");
     }
 
+    #[test]
+    fn test_heatmap_weeks() {
+        let now = Local::now().timestamp();
+        let timestamps = [now, now, now - 86_400, now - 30 * 86_400];
+        let weeks = build_heatmap(&timestamps, now);
+
+        assert!(weeks.len() >= 5, "Covers at least 30 days: {}", weeks.len());
+        assert!(weeks[0].label.is_some(), "The first week is labelled");
+
+        let counted: usize = weeks
+            .iter()
+            .flat_map(|week| week.days.iter().flatten())
+            .sum();
+        assert_eq!(counted, timestamps.len());
+
+        // Days after today are left out of the last week
+        let today = get_datetime_local(now).weekday().num_days_from_monday() as usize;
+        let last_week = &weeks.last().unwrap().days;
+        assert!(last_week[today].is_some_and(|count| count >= 2));
+        assert!(last_week[today + 1..].iter().all(Option::is_none));
+
+        assert!(build_heatmap(&[], now).is_empty());
+    }
+
+    #[test]
+    fn test_previous_window() {
+        let window = Window {
+            start: Some(1_000),
+            end: Some(1_600),
+        };
+        let previous = window.previous(9_999).unwrap();
+        assert_eq!((previous.start, previous.end), (Some(400), Some(1_000)));
+
+        let open_ended = Window {
+            start: Some(1_000),
+            end: None,
+        };
+        let previous_open = open_ended.previous(1_200).unwrap();
+        assert_eq!(
+            (previous_open.start, previous_open.end),
+            (Some(800), Some(1_000))
+        );
+
+        let no_start = Window {
+            start: None,
+            end: Some(1_000),
+        };
+        assert!(
+            no_start.previous(1_200).is_err(),
+            "A window needs a start to have a length"
+        );
+    }
+
+    #[test]
+    fn test_counted_directories() {
+        let home = Path::new("/home/user");
+        let counted =
+            |path| -> Vec<&Path> { counted_directories(Path::new(path), Some(home)).collect() };
+
+        assert_eq!(
+            counted("/home/user/src/memy/main.rs"),
+            [
+                Path::new("/home/user/src/memy"),
+                Path::new("/home/user/src")
+            ]
+        );
+        assert_eq!(counted("/home/user/notes.txt"), [home]);
+        assert_eq!(
+            counted("/etc/ssh/sshd_config"),
+            [Path::new("/etc/ssh"), Path::new("/etc")]
+        );
+        assert_eq!(counted("/etc/hosts"), [Path::new("/etc")]);
+    }
+
+    #[test]
+    fn test_tally_into_top() {
+        let mut tally = Tally::default();
+        tally.add("b".to_owned(), 1.0);
+        tally.add("a".to_owned(), 1.0);
+        tally.add("c".to_owned(), 0.5);
+        tally.add("c".to_owned(), 1.0);
+        tally.add("d".to_owned(), 0.25);
+
+        let top = tally.into_top(3);
+        let names: Vec<&str> = top.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["c", "a", "b"], "Heaviest first, ties by name");
+        assert_eq!(top[0].paths, 2);
+        assert!((top[0].weight - 1.5).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_histogram_empty() {
         let result = build_histogram(&[]);
@@ -529,6 +1158,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_day_buckets_stay_on_local_midnight() {
+        // A year of steps crosses both DST transitions in any zone that has them; fixed
+        // 86400s steps would drift an hour off midnight and skip or repeat a day.
+        let mut bucket = TimeGranularity::Day.starting_timestamp(1_704_067_200); // 2024-01-01
+        for _ in 0..366 {
+            let next = TimeGranularity::Day.next_bucket(bucket);
+            assert_eq!(
+                TimeGranularity::Day.starting_timestamp(next),
+                next,
+                "Day bucket after {bucket} is not a local midnight"
+            );
+            assert_eq!(
+                get_datetime_local(next).date_naive(),
+                get_datetime_local(bucket).date_naive() + chrono::Days::new(1),
+                "Day bucket after {bucket} is not the next calendar day"
+            );
+            bucket = next;
+        }
+    }
+
     mod proptests {
         use super::*;
         use proptest::prelude::*;
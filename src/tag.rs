@@ -0,0 +1,60 @@
+use core::error::Error;
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+use std::io::{Write as _, stdout};
+use tracing::{debug, info, instrument};
+
+use crate::utils::cli::{TagAction, TagArgs, TagChangeArgs, TagListArgs};
+use crate::utils::db;
+
+fn change(conn: &Connection, args: &TagChangeArgs, add: bool) -> Result<(), Box<dyn Error>> {
+    let paths = db::resolve_stored_paths(conn, &args.paths)?;
+
+    let changed = if add {
+        db::add_tag(conn, &paths, &args.tag)?
+    } else {
+        db::remove_tag(conn, &paths, &args.tag)?
+    };
+    debug!("{changed} paths changed");
+
+    let action = if add { "added to" } else { "removed from" };
+    for changed_path in &paths {
+        info!("Tag {} {action} {changed_path}", args.tag);
+    }
+
+    Ok(())
+}
+
+fn list(conn: &Connection, args: &TagListArgs) -> Result<(), Box<dyn Error>> {
+    let all_tags = db::get_tags(conn)?;
+
+    let tags: BTreeSet<String> = if let Some(raw_path) = &args.path {
+        let stored_path = db::resolve_stored_paths(conn, core::slice::from_ref(raw_path))?
+            .pop()
+            .expect("One path resolved");
+        all_tags.get(&stored_path).cloned().unwrap_or_default()
+    } else {
+        all_tags.into_values().flatten().collect()
+    };
+
+    let mut stdout_handle = stdout().lock();
+    for tag in &tags {
+        writeln!(stdout_handle, "{tag}")?;
+    }
+
+    Ok(())
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &TagArgs) -> Result<(), Box<dyn Error>> {
+    let db_connection = db::open()?;
+
+    let result = match &args.action {
+        TagAction::Add(change_args) => change(&db_connection, change_args, true),
+        TagAction::Remove(change_args) => change(&db_connection, change_args, false),
+        TagAction::List(list_args) => list(&db_connection, list_args),
+    };
+
+    db::close(db_connection)?;
+    result
+}
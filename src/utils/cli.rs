@@ -1,4 +1,4 @@
-use clap::builder::{PossibleValuesParser, Styles};
+use clap::builder::{NonEmptyStringValueParser, PossibleValuesParser, Styles};
 use clap::error::ErrorKind;
 use clap::{Args, CommandFactory as _, Parser, Subcommand};
 
@@ -10,6 +10,52 @@ pub enum SortOrder {
     Ascending,
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Keywords must appear in order, the last one in the last path component (default).
+    Zoxide,
+    /// Keyword characters must appear in order, scored fzf-style and combined with frecency.
+    Fuzzy,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// Photos and pictures, e.g. png, jpg, svg.
+    Image,
+    /// Videos, e.g. mp4, mkv, webm.
+    Video,
+    /// Office documents, PDFs, ebooks and prose, e.g. pdf, docx, md.
+    Document,
+    /// Source code, scripts and configuration, e.g. rs, py, toml.
+    Source,
+    /// Archives and compressed files, e.g. zip, tar.gz, 7z.
+    Archive,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Add the noted counts together and keep the most recent timestamp (default).
+    Sum,
+    /// Keep the higher of the two noted counts and the most recent timestamp.
+    Max,
+    /// Keep whichever entry was noted most recently, as-is.
+    Newest,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsWeight {
+    /// Add up how many times the paths were noted (default).
+    Count,
+    /// Add up the paths' frecency scores.
+    Frecency,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsCompare {
+    /// The window of the same length just before it.
+    Previous,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "memy",
@@ -55,15 +101,51 @@ fn parse_key_val(key_value: &str) -> Result<(String, String), String> {
     Ok((key, value))
 }
 
+fn parse_tag(tag: &str) -> Result<String, String> {
+    if tag.is_empty() {
+        return Err("Tags cannot be empty".to_owned());
+    }
+
+    if tag.contains(|c: char| c.is_whitespace() || c == ',' || c == ';') {
+        return Err(format!(
+            "Invalid tag '{tag}': tags cannot contain whitespace, ',' or ';'"
+        ));
+    }
+
+    Ok(tag.to_owned())
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Note usage of (add to database) one or more paths
     Note(NoteArgs),
     /// List paths by frecency score
     #[command(visible_alias = "ls")]
-    List(ListArgs),
+    List(Box<ListArgs>),
+    /// Remove paths from the database
+    Forget(ForgetArgs),
+    /// Pin paths so they always list first, or show pinned paths if none are given
+    Pin(PinArgs),
+    /// Unpin previously pinned paths
+    Unpin(PinArgs),
+    /// Add, remove or list tags on paths
+    Tag(TagArgs),
     /// Show statistics about noted paths
     Stats(StatsArgs),
+    /// Show how a path's frecency score and rank were computed
+    Explain(ExplainArgs),
+    /// Export the whole database as JSON or CSV
+    Export(ExportArgs),
+    /// Import a database previously written by `memy export`
+    Import(ImportArgs),
+    /// Merge another memy database (e.g. from another machine) into this one
+    Merge(MergeArgs),
+    /// Check the database, config and hooks for problems
+    Doctor(DoctorArgs),
+    /// Keep the database and config loaded and note paths forwarded by `memy note` over a socket
+    Daemon {},
+    /// Watch directories and note files as they are written or opened (Linux only)
+    Watch(WatchArgs),
     /// Show contents of a memy hook
     Hook {
         #[arg(value_enum)]
@@ -101,6 +183,76 @@ pub struct NoteArgs {
     /// One or more paths to note
     #[arg(value_name = "PATHS")]
     pub paths: Vec<String>,
+
+    /// Record what noted the paths (e.g. the editor or shell hook), for `memy list --source`
+    #[arg(long, value_name = "NAME", value_parser = NonEmptyStringValueParser::new())]
+    pub source: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct PinArgs {
+    /// Paths to pin or unpin; they must already be in the database
+    #[arg(value_name = "PATHS")]
+    pub paths: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TagArgs {
+    #[command(subcommand)]
+    pub action: TagAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TagAction {
+    /// Add a tag to one or more paths
+    Add(TagChangeArgs),
+    /// Remove a tag from one or more paths
+    Remove(TagChangeArgs),
+    /// List all tags, or the tags on a single path
+    List(TagListArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TagChangeArgs {
+    /// The tag to add or remove
+    #[arg(value_name = "TAG", value_parser = parse_tag)]
+    pub tag: String,
+
+    /// Paths to change; they must already be in the database
+    #[arg(value_name = "PATHS", required = true)]
+    pub paths: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TagListArgs {
+    /// Only list the tags on this path
+    #[arg(value_name = "PATH")]
+    pub path: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ForgetArgs {
+    /// Exact paths to forget
+    #[arg(value_name = "PATHS")]
+    pub paths: Vec<String>,
+
+    /// Forget paths matching this keyword, using the same matching as `memy list` (repeat for
+    /// multiple ordered keywords)
+    #[arg(short, long = "keyword", value_name = "KEYWORD")]
+    pub keywords: Vec<String>,
+
+    /// Forget paths matching this gitignore-style pattern, the same syntax as the denylist (can
+    /// be repeated)
+    #[arg(short, long = "glob", value_name = "PATTERN")]
+    pub globs: Vec<String>,
+
+    /// Show the paths that would be forgotten, and how many, without removing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't ask for confirmation, however many paths match
+    #[arg(short, long)]
+    pub yes: bool,
 }
 
 #[derive(Args, Debug)]
@@ -118,14 +270,54 @@ pub struct ListArgs {
     pub directories_only: bool,
 
     /// Output format
-    #[arg(long, default_value = "plain", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["plain", "csv", "json"]))]
+    #[arg(long, default_value = "plain", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["plain", "csv", "json", "jsonl", "tsv"]))]
     pub format: String,
 
+    /// Format each result with a template instead, e.g. `{path}\t{count}\t{last_noted:relative}`.
+    /// Fields: `path`, `frecency` (or `{frecency:.N}` for N decimal places), `count`, `last_noted`
+    /// (or `{last_noted:relative}` / `{last_noted:unix}`), `file_type`, `pinned`, `tags`
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "format")]
+    pub template: Option<String>,
+
+    /// End each result with a NUL character instead of a newline, for paths containing newlines
+    #[arg(short = '0', long = "null")]
+    pub null_separated: bool,
+
+    /// Show frecency, noted count, time since last noted and file type in aligned columns before
+    /// each path
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["format", "template", "null_separated", "output_filter"]
+    )]
+    pub long: bool,
+
     /// Only show paths noted after a specific time. Can use durations (e.g., '4d', '3h', '4d3h') or
     /// ISO-8601 date/timestamps (e.g., '2025-01-01', '2025-01-01T12:00:00')
     #[arg(long, value_name = "TIME")]
     pub newer_than: Option<String>,
 
+    /// Only show paths last noted before a specific time, in the same formats as --newer-than
+    #[arg(long, value_name = "TIME")]
+    pub older_than: Option<String>,
+
+    /// Only show paths last noted between two times, in the same formats as --newer-than (e.g.,
+    /// '2025-01-01..2025-02-01', '14d..7d'); either end can be left out
+    #[arg(long, value_name = "A..B")]
+    pub between: Option<String>,
+
+    /// Only show paths noted at least N times
+    #[arg(long, value_name = "N")]
+    pub min_count: Option<u64>,
+
+    /// Only show paths noted at most N times
+    #[arg(long, value_name = "N")]
+    pub max_count: Option<u64>,
+
+    /// Only show paths with a frecency score of at least this, as shown by --long
+    #[arg(long, value_name = "SCORE")]
+    pub min_score: Option<f64>,
+
     /// Return only the top N results (most or least frecent, depending on --sort)
     #[arg(long, value_name = "N")]
     pub limit_results: Option<usize>,
@@ -137,6 +329,70 @@ pub struct ListArgs {
     #[arg(long, value_name = "ORDER")]
     pub sort: Option<SortOrder>,
 
+    /// Multiply the frecency of paths in or under the current directory by this factor; the bonus
+    /// halves for each level further away a path is (overrides `cwd_boost` config)
+    #[arg(long, value_name = "FACTOR")]
+    pub cwd_boost: Option<f64>,
+
+    /// Let the frecency of each file flow to the directories above it, decaying by
+    /// `derive_dirs_decay` per level (always on with the `derive_dirs` config)
+    #[arg(long)]
+    pub derive_dirs: bool,
+
+    /// How keywords are matched against paths (overrides `keyword_match` config)
+    #[arg(long = "match", value_name = "MODE")]
+    pub match_mode: Option<MatchMode>,
+
+    /// Only list paths noted at least once with this `memy note --source` (repeat to allow any of
+    /// several sources)
+    #[arg(long = "source", value_name = "NAME")]
+    pub sources: Vec<String>,
+
+    /// Only list paths with this tag (repeat to require several tags)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Only list paths matching this gitignore-style pattern, the same syntax as the denylist
+    /// (repeat to allow any of several patterns)
+    #[arg(long = "glob", value_name = "PATTERN")]
+    pub globs: Vec<String>,
+
+    /// Don't list paths matching this gitignore-style pattern (can be repeated)
+    #[arg(long = "exclude-glob", value_name = "PATTERN")]
+    pub exclude_globs: Vec<String>,
+
+    /// Only list paths where this regular expression matches somewhere in the full path (repeat
+    /// to allow any of several expressions)
+    #[arg(long = "regex", value_name = "REGEX")]
+    pub regexes: Vec<String>,
+
+    /// Only list files with one of these extensions, case-insensitive (comma-separated or
+    /// repeated, e.g. 'md,txt')
+    #[arg(long = "ext", value_name = "EXT", value_delimiter = ',')]
+    pub extensions: Vec<String>,
+
+    /// Only list files of this kind, judged by extension (repeat to allow any of several kinds)
+    #[arg(long = "kind", value_name = "KIND")]
+    pub kinds: Vec<FileKind>,
+
+    /// With --kind, also read the start of files with no or unknown extensions to recognise
+    /// common formats by their contents
+    #[arg(long, requires = "kinds")]
+    pub sniff: bool,
+
+    /// Only list paths below this directory
+    #[arg(long, value_name = "DIR", group = "root")]
+    pub under: Option<String>,
+
+    /// Only list paths below the root of the git work tree containing the current directory
+    #[arg(long, group = "root")]
+    pub repo: bool,
+
+    /// Show paths relative to the --under directory or --repo root (not applied to what is passed
+    /// to an output filter, which gets full paths)
+    #[arg(long, requires = "root")]
+    pub relative: bool,
+
     /// Show paths under the home directory using `~` prefixes
     #[arg(long)]
     pub pretty_paths: bool,
@@ -159,8 +415,8 @@ pub struct ListArgs {
     #[arg(long, hide = true)]
     pub zoxide_compatible: bool,
 
-    /// Search keywords (case-insensitive, must appear in path in the order provided, last keyword must appear in
-    /// the last path component)
+    /// Search keywords (case-insensitive, must appear in path in the order provided; with the default zoxide
+    /// matching, the last keyword must appear in the last path component)
     #[arg(value_name = "KEYWORDS", num_args = 0..)]
     pub keywords: Vec<String>,
 }
@@ -180,11 +436,126 @@ pub struct OpenArgs {
     pub path: String,
 }
 
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Output format
+    #[arg(long, default_value = "json", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["json", "csv"]))]
+    pub format: String,
+
+    /// Write the export to a file instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// File written by `memy export`
+    #[arg(long, value_name = "FILE")]
+    pub file: String,
+
+    /// Input format (defaults to the file extension, or json)
+    #[arg(long, value_name = "FORMAT", value_parser = PossibleValuesParser::new(["json", "csv"]))]
+    pub format: Option<String>,
+
+    /// How to combine an imported path with one already in the database
+    #[arg(long, value_name = "STRATEGY", default_value = "sum")]
+    pub strategy: MergeStrategy,
+
+    /// Show what would change without modifying the database
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct MergeArgs {
+    /// The other memy.sqlite3 database; it is only read, never changed
+    #[arg(value_name = "OTHER_DB")]
+    pub file: String,
+
+    /// How to combine a path with one already in the database
+    #[arg(long, value_name = "STRATEGY", default_value = "sum")]
+    pub strategy: MergeStrategy,
+
+    /// Show what would change without modifying the database
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Remove missing and denied paths, merge duplicates, and compact the database
+    #[arg(long)]
+    pub fix: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// The directories to watch, along with their subdirectories
+    #[arg(value_name = "DIR", required = true)]
+    pub dirs: Vec<String>,
+
+    /// Only note files when they are written, not when they are opened and read
+    #[arg(long)]
+    pub writes_only: bool,
+
+    /// How long a file must go without further activity before it is noted, so rapid writes are
+    /// noted once (e.g. '500ms', '2s')
+    #[arg(long, value_name = "DURATION", default_value = "1s")]
+    pub debounce: String,
+
+    /// Don't watch subdirectories with this name (repeat for more), as well as the defaults
+    #[arg(long, value_name = "NAME")]
+    pub exclude: Vec<String>,
+
+    /// Watch build output and cache directories such as `target` and `node_modules` too
+    #[arg(long)]
+    pub no_default_excludes: bool,
+
+    /// What to record as having noted the files, for `memy list --source`
+    #[arg(long, value_name = "NAME", default_value = "watch", value_parser = NonEmptyStringValueParser::new())]
+    pub source: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    /// The path to explain; it must already be in the database
+    #[arg(value_name = "PATH")]
+    pub path: String,
+}
+
 #[derive(Args, Debug)]
 pub struct StatsArgs {
     /// Output format
     #[arg(long, default_value = "plain", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["plain", "json"]))]
     pub format: String,
+
+    /// How many directories, extensions, kinds and repositories to show in each breakdown (0 leaves
+    /// the breakdowns out)
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top: usize,
+
+    /// What to weight the breakdowns by
+    #[arg(long, value_name = "WEIGHT", default_value = "count")]
+    pub weight: StatsWeight,
+
+    /// Only cover notes made after a specific time, in the same formats as `memy list
+    /// --newer-than`
+    #[arg(long, value_name = "TIME")]
+    pub newer_than: Option<String>,
+
+    /// Only cover notes made before a specific time, in the same formats as `memy list
+    /// --newer-than`
+    #[arg(long, value_name = "TIME")]
+    pub older_than: Option<String>,
+
+    /// Compare the window given by --newer-than and --older-than with another one, showing the
+    /// changes in paths and notes and the paths that moved the most
+    #[arg(long, value_name = "WINDOW", requires = "newer_than")]
+    pub compare: Option<StatsCompare>,
+
+    /// Show a calendar heatmap of notes per day instead of the time distribution chart
+    #[arg(long)]
+    pub heatmap: bool,
 }
 
 #[must_use]
@@ -12,7 +12,7 @@ use tracing::instrument;
 use tracing::{debug, error};
 use xdg::BaseDirectories;
 
-use super::cli::SortOrder;
+use super::cli::{MatchMode, SortOrder};
 use super::denylist_default;
 use super::path::expand_tilde_in_path;
 
@@ -29,6 +29,19 @@ impl<'de> serde::Deserialize<'de> for SortOrder {
     }
 }
 
+impl<'de> serde::Deserialize<'de> for MatchMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "zoxide" => Ok(Self::Zoxide),
+            "fuzzy" => Ok(Self::Fuzzy),
+            other => Err(de::Error::custom(format!(
+                "invalid keyword match mode '{other}': expected 'zoxide' or 'fuzzy'"
+            ))),
+        }
+    }
+}
+
 pub type RecencyBias = f64;
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
@@ -39,6 +52,15 @@ pub enum DeniedFilesOnList {
     Delete,
 }
 
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FrecencyAlgorithm {
+    Linear,
+    Zoxide,
+    Fasd,
+    HalfLife,
+}
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct MemyConfig {
@@ -52,9 +74,19 @@ pub struct MemyConfig {
     pub use_tilde_on_list: Option<bool>,
     #[serde(default, deserialize_with = "validate_recency_bias")]
     pub recency_bias: Option<RecencyBias>,
+    pub frecency_algorithm: Option<FrecencyAlgorithm>,
+    #[serde(default, deserialize_with = "validate_frecency_half_life_days")]
+    pub frecency_half_life_days: Option<f64>,
+    #[serde(default, deserialize_with = "validate_cwd_boost")]
+    pub cwd_boost: Option<f64>,
+    pub keyword_match: Option<MatchMode>,
+    pub derive_dirs: Option<bool>,
+    #[serde(default, deserialize_with = "validate_derive_dirs_decay")]
+    pub derive_dirs_decay: Option<f64>,
     pub missing_files_delete_from_db_after: Option<i32>,
     pub memy_output_filter: Option<String>,
     pub default_sort: Option<SortOrder>,
+    pub forget_confirm_threshold: Option<usize>,
 }
 
 fn validate_recency_bias<'de, D>(deserializer: D) -> Result<Option<RecencyBias>, D::Error>
@@ -70,12 +102,56 @@ where
     Ok(value)
 }
 
+fn validate_frecency_half_life_days<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<f64> = Option::deserialize(deserializer)?;
+    if let Some(v) = value
+        && v <= 0.0
+    {
+        return Err(de::Error::custom(
+            "frecency_half_life_days must be greater than 0",
+        ));
+    }
+    Ok(value)
+}
+
+fn validate_cwd_boost<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<f64> = Option::deserialize(deserializer)?;
+    if let Some(v) = value
+        && v < 0.0
+    {
+        return Err(de::Error::custom("cwd_boost must not be negative"));
+    }
+    Ok(value)
+}
+
+fn validate_derive_dirs_decay<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<f64> = Option::deserialize(deserializer)?;
+    if let Some(v) = value
+        && (v <= 0.0 || v > 1.0)
+    {
+        return Err(de::Error::custom(
+            "derive_dirs_decay must be greater than 0 and at most 1",
+        ));
+    }
+    Ok(value)
+}
+
 static CONFIG: OnceLock<MemyConfig> = OnceLock::new();
+static CONFIG_OVERRIDES: OnceLock<Vec<(String, String)>> = OnceLock::new();
 
 const TEMPLATE_CONFIG: &str = include_str!("../../config/template-memy.toml");
 
 #[instrument(level = "trace")]
-fn get_config_file_path() -> PathBuf {
+pub fn get_config_file_path() -> PathBuf {
     if let Ok(dir) = env::var("MEMY_CONFIG_DIR") {
         let path = PathBuf::from(dir).join("memy.toml");
         return path;
@@ -168,6 +244,10 @@ pub fn load_config(overrides: Vec<(String, String)>) {
         // ***
     }
 
+    CONFIG_OVERRIDES
+        .set(overrides.clone())
+        .expect("Could not set configuration overrides");
+
     let is_pretty_path_present = overrides.iter().any(|(key, _)| key == "use_pretty_paths");
 
     for (key, value_str) in overrides {
@@ -208,6 +288,11 @@ fn get_config() -> &'static MemyConfig {
     CONFIG.get().expect("Config not initialized")
 }
 
+/// Returns the `--config` overrides the configuration was loaded with.
+pub fn get_config_overrides() -> &'static [(String, String)] {
+    CONFIG_OVERRIDES.get().map_or(&[], Vec::as_slice)
+}
+
 pub fn output_template_config() -> Result<(), Box<dyn Error>> {
     write!(stdout(), "{TEMPLATE_CONFIG}")?;
     Ok(())
@@ -217,32 +302,41 @@ pub fn get_import_on_first_use() -> bool {
     get_config().import_on_first_use.unwrap_or(true)
 }
 
-fn build_gitignore(patterns: Vec<String>) -> Gitignore {
+/// Builds a matcher that records the file each pattern came from, which a match reports through
+/// `Glob::from`.
+pub fn build_gitignore(patterns: impl IntoIterator<Item = (Option<PathBuf>, String)>) -> Gitignore {
     let mut builder = GitignoreBuilder::new("/");
-    for pat in patterns {
+    for (from, pat) in patterns {
         builder
-            .add_line(None, &pat)
+            .add_line(from, &pat)
             .unwrap_or_else(|_| panic!("Pattern {pat} not valid."));
     }
     builder.build().expect("Failed to build denylist matcher")
 }
 
+/// Builds the matcher for the default denylist followed by the `denylist` config option. Patterns
+/// from the config option are recorded as coming from the config file; default ones from nowhere.
 pub fn get_denylist_matcher() -> Gitignore {
     let config = get_config();
+    let config_file_path = get_config_file_path();
 
-    let mut combined_denylist: Vec<String> = denylist_default::DEFAULT_DENYLIST
+    let combined_denylist: Vec<(Option<PathBuf>, String)> = denylist_default::DEFAULT_DENYLIST
         .iter()
-        .map(std::string::ToString::to_string)
+        .map(|pattern| (None, (*pattern).to_owned()))
+        .chain(
+            config
+                .denylist
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|pattern| {
+                    (
+                        Some(config_file_path.clone()),
+                        expand_tilde_in_path(&pattern).to_string_lossy().to_string(),
+                    )
+                }),
+        )
         .collect();
-    combined_denylist.append(
-        &mut config
-            .denylist
-            .clone()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|pattern| expand_tilde_in_path(&pattern).to_string_lossy().to_string())
-            .collect(),
-    );
 
     debug!("Combined denylist: {combined_denylist:?}");
 
@@ -279,6 +373,32 @@ pub fn get_recency_bias() -> RecencyBias {
     get_config().recency_bias.unwrap_or(0.5)
 }
 
+pub fn get_frecency_algorithm() -> FrecencyAlgorithm {
+    get_config()
+        .frecency_algorithm
+        .unwrap_or(FrecencyAlgorithm::Linear)
+}
+
+pub fn get_frecency_half_life_days() -> f64 {
+    get_config().frecency_half_life_days.unwrap_or(7.0)
+}
+
+pub fn get_cwd_boost() -> f64 {
+    get_config().cwd_boost.unwrap_or(1.0)
+}
+
+pub fn get_keyword_match() -> MatchMode {
+    get_config().keyword_match.unwrap_or(MatchMode::Zoxide)
+}
+
+pub fn get_derive_dirs() -> bool {
+    get_config().derive_dirs.unwrap_or(false)
+}
+
+pub fn get_derive_dirs_decay() -> f64 {
+    get_config().derive_dirs_decay.unwrap_or(0.5)
+}
+
 pub fn get_missing_files_delete_from_db_after() -> i32 {
     get_config()
         .missing_files_delete_from_db_after
@@ -300,6 +420,10 @@ pub fn get_default_sort() -> SortOrder {
         .unwrap_or(SortOrder::Descending)
 }
 
+pub fn get_forget_confirm_threshold() -> usize {
+    get_config().forget_confirm_threshold.unwrap_or(10)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,11 +441,8 @@ mod tests {
         }
 
         fn wrapper(patterns: &[&str], path: &str, is_dir: bool) -> OwnedMatch {
-            let strings: Vec<String> = patterns
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect();
-            let gitignore = build_gitignore(strings);
+            let gitignore =
+                build_gitignore(patterns.iter().map(|pattern| (None, (*pattern).to_owned())));
             let matched = gitignore.matched_path_or_any_parents(path, is_dir);
 
             OwnedMatch {
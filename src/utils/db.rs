@@ -1,24 +1,42 @@
 use core::error::Error;
-use rusqlite::{Connection, OptionalExtension as _};
+use rusqlite::{Connection, OptionalExtension as _, params, params_from_iter};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::debug;
 use tracing::instrument;
 use xdg::BaseDirectories;
 
 use super::config;
+use super::path;
+use super::time::get_timestamp_now;
 use super::types::{NotedCount, UnixTimestamp};
 use crate::import;
 
-const DB_VERSION: i32 = 2;
+const DB_VERSION: i32 = 5;
 const DB_FILENAME: &str = "memy.sqlite3";
 
-#[derive(serde::Serialize)]
+/// The `state` key holding when the `events` table started to record every note. Before then,
+/// paths only have an event for their most recent note: those from before the v3 migration, and
+/// those imported or merged in without their own history.
+const EVENTS_COMPLETE_SINCE_KEY: &str = "events_complete_since";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct TablePathsEntry {
     pub path: String,
     pub noted_count: NotedCount,
     pub last_noted_timestamp: UnixTimestamp,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// A row of the `events` table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableEventsEntry {
+    pub timestamp: UnixTimestamp,
+    pub source: Option<String>,
+    pub cwd: Option<String>,
 }
 
 pub trait FromRow: Sized {
@@ -31,12 +49,13 @@ impl FromRow for TablePathsEntry {
             path: row.get("path")?,
             noted_count: row.get("noted_count")?,
             last_noted_timestamp: row.get("last_noted_timestamp")?,
+            pinned: row.get("pinned")?,
         })
     }
 }
 
 #[instrument(level = "trace")]
-fn get_db_version(conn: &Connection) -> i32 {
+pub fn get_db_version(conn: &Connection) -> i32 {
     conn.query_row("PRAGMA user_version;", [], |row| row.get(0))
         .expect("Failed to read database version")
 }
@@ -52,6 +71,11 @@ fn get_db_path() -> PathBuf {
     )
 }
 
+#[must_use]
+pub fn get_db_file_path() -> PathBuf {
+    get_db_path().join(DB_FILENAME)
+}
+
 fn create_state_table(conn: &Connection, breaking_change_sort_warning_count_remaining: i32) {
     conn.execute(
         "CREATE TABLE state (key TEXT PRIMARY KEY, value TEXT NOT NULL)",
@@ -65,19 +89,53 @@ fn create_state_table(conn: &Connection, breaking_change_sort_warning_count_rema
     ).expect("Cannot insert into state table");
 }
 
+fn create_events_table(conn: &Connection) {
+    conn.execute(
+        "CREATE TABLE events (
+            id INTEGER PRIMARY KEY,
+            path TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            source TEXT,
+            cwd TEXT
+        )",
+        [],
+    )
+    .expect("Failed to create events table");
+
+    conn.execute("CREATE INDEX events_path ON events (path)", [])
+        .expect("Failed to create events path index");
+    conn.execute("CREATE INDEX events_timestamp ON events (timestamp)", [])
+        .expect("Failed to create events timestamp index");
+}
+
+fn create_tags_table(conn: &Connection) {
+    conn.execute(
+        "CREATE TABLE tags (
+            path TEXT NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (path, tag)
+        )",
+        [],
+    )
+    .expect("Failed to create tags table");
+}
+
 #[instrument(level = "trace")]
 fn init_db(conn: &Connection) {
     conn.execute(
         "CREATE TABLE paths (
             path TEXT PRIMARY KEY,
             noted_count INTEGER NOT NULL,
-            last_noted_timestamp INTEGER NOT NULL
+            last_noted_timestamp INTEGER NOT NULL,
+            pinned INTEGER NOT NULL DEFAULT 0
         )",
         [],
     )
     .expect("Failed to initialize database");
 
     create_state_table(conn, 0);
+    create_events_table(conn);
+    create_tags_table(conn);
 
     conn.execute(&format!("PRAGMA user_version = {DB_VERSION};"), [])
         .expect("Failed to set database version");
@@ -95,6 +153,59 @@ fn migrate_v1_to_v2(conn: &Connection) {
     debug!("Migration from v1 to v2 complete");
 }
 
+/// Earlier versions only kept the most recent note for each path, so one synthetic event is
+/// created per existing row at its `last_noted_timestamp`.
+#[instrument(level = "trace")]
+fn migrate_v2_to_v3(conn: &Connection) {
+    debug!("Migrating database from version 2 to version 3");
+
+    create_events_table(conn);
+
+    let synthetic_events = conn
+        .execute(
+            "INSERT INTO events (path, timestamp) SELECT path, last_noted_timestamp FROM paths",
+            [],
+        )
+        .expect("Failed to create synthetic events");
+    if synthetic_events > 0 {
+        set_events_complete_since(conn, get_timestamp_now())
+            .expect("Failed to record when events are complete from");
+    }
+
+    conn.execute("PRAGMA user_version = 3;", [])
+        .expect("Failed to set database version to 3");
+
+    debug!("Migration from v2 to v3 complete");
+}
+
+#[instrument(level = "trace")]
+fn migrate_v3_to_v4(conn: &Connection) {
+    debug!("Migrating database from version 3 to version 4");
+
+    conn.execute(
+        "ALTER TABLE paths ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+        [],
+    )
+    .expect("Failed to add pinned column");
+
+    conn.execute("PRAGMA user_version = 4;", [])
+        .expect("Failed to set database version to 4");
+
+    debug!("Migration from v3 to v4 complete");
+}
+
+#[instrument(level = "trace")]
+fn migrate_v4_to_v5(conn: &Connection) {
+    debug!("Migrating database from version 4 to version 5");
+
+    create_tags_table(conn);
+
+    conn.execute("PRAGMA user_version = 5;", [])
+        .expect("Failed to set database version to 5");
+
+    debug!("Migration from v4 to v5 complete");
+}
+
 fn get_warning_count_left(conn: &Connection) -> i64 {
     conn.query_row(
         "SELECT value FROM state WHERE key = 'breaking_change_sort_warning_count_remaining'",
@@ -116,7 +227,7 @@ fn decrement_warning_count(conn: &Connection) {
 }
 
 pub fn should_show_breaking_change_sort_warning() -> bool {
-    let db_file = get_db_path().join(DB_FILENAME);
+    let db_file = get_db_file_path();
     if !db_file.exists() {
         return false;
     }
@@ -134,6 +245,46 @@ pub fn should_show_breaking_change_sort_warning() -> bool {
     should_show
 }
 
+/// Refuses databases from unknown versions of memy, and brings older ones up to date.
+fn check_version_and_migrate(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    let version = get_db_version(conn);
+
+    if !(1..=DB_VERSION).contains(&version) {
+        return Err(
+            format!("Database version mismatch: expected {DB_VERSION}, found {version}.").into(),
+        );
+    }
+
+    if version < 2 {
+        migrate_in_transaction(conn, migrate_v1_to_v2)?;
+    }
+
+    if version < 3 {
+        migrate_in_transaction(conn, migrate_v2_to_v3)?;
+    }
+
+    if version < 4 {
+        migrate_in_transaction(conn, migrate_v3_to_v4)?;
+    }
+
+    if version < 5 {
+        migrate_in_transaction(conn, migrate_v4_to_v5)?;
+    }
+
+    Ok(())
+}
+
+/// Runs `migration` in one transaction, so if it's interrupted the database is left as it was,
+/// including its version, and the migration runs again in full next time.
+fn migrate_in_transaction(
+    conn: &Connection,
+    migration: fn(&Connection),
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+    migration(&tx);
+    tx.commit()
+}
+
 #[instrument(level = "trace")]
 pub fn open() -> Result<Connection, Box<dyn Error>> {
     let db_path = get_db_path();
@@ -142,22 +293,13 @@ pub fn open() -> Result<Connection, Box<dyn Error>> {
         fs::create_dir_all(&db_path)?;
     }
 
-    let db_file = db_path.join(DB_FILENAME);
+    let db_file = get_db_file_path();
     let db_path_exists = db_file.exists();
     let mut conn = Connection::open(&db_file).expect("Failed to open memy database");
 
     if db_path_exists {
         debug!("Database at {} does exist", db_file.to_string_lossy());
-        let version = get_db_version(&conn);
-
-        if version == 1 {
-            migrate_v1_to_v2(&conn);
-        } else if !(1..=2).contains(&version) {
-            return Err(format!(
-                "Database version mismatch: expected {DB_VERSION}, found {version}."
-            )
-            .into());
-        }
+        check_version_and_migrate(&conn)?;
     } else {
         debug!("Database at {} does not exist", db_file.to_string_lossy());
         init_db(&conn);
@@ -171,6 +313,48 @@ pub fn open() -> Result<Connection, Box<dyn Error>> {
     Ok(conn)
 }
 
+/// Opens another memy database without changing it. It's copied into memory, then checked and
+/// migrated there just as [`open`] would.
+#[instrument(level = "trace")]
+pub fn open_other(db_file: &Path) -> Result<Connection, Box<dyn Error>> {
+    if !db_file.is_file() {
+        return Err(format!("{} does not exist", db_file.display()).into());
+    }
+
+    let conn = Connection::open_in_memory().expect("Failed to open in-memory database");
+    let escaped_path = db_file
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23");
+    let uri = format!("file:{escaped_path}?mode=ro");
+    conn.execute("ATTACH DATABASE ?1 AS other", params![uri])
+        .map_err(|e| format!("Cannot open {}: {e}", db_file.display()))?;
+
+    let tables: Vec<(String, String)> = conn
+        .prepare("SELECT name, sql FROM other.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("{} is not a memy database: {e}", db_file.display()))?;
+
+    for (name, sql) in tables {
+        conn.execute(&sql, [])?;
+        conn.execute(
+            &format!("INSERT INTO main.{name} SELECT * FROM other.{name}"),
+            [],
+        )?;
+    }
+
+    let version: i32 = conn.query_row("PRAGMA other.user_version;", [], |row| row.get(0))?;
+    conn.execute("DETACH DATABASE other", [])?;
+    conn.execute(&format!("PRAGMA user_version = {version};"), [])?;
+
+    check_version_and_migrate(&conn)?;
+
+    debug!("Other database {} opened", db_file.display());
+    Ok(conn)
+}
+
 #[instrument(level = "trace")]
 pub fn close(conn: Connection) -> Result<(), Box<dyn Error>> {
     conn.execute("PRAGMA optimize;", []).optional()?;
@@ -179,7 +363,7 @@ pub fn close(conn: Connection) -> Result<(), Box<dyn Error>> {
 
 pub fn get_rows(conn: &Connection) -> Result<Vec<TablePathsEntry>, rusqlite::Error> {
     let mut stmt = conn
-        .prepare("SELECT path, noted_count, last_noted_timestamp FROM paths")
+        .prepare("SELECT path, noted_count, last_noted_timestamp, pinned FROM paths")
         .expect("Select failed");
 
     stmt.query_map([], TablePathsEntry::from_row)
@@ -187,6 +371,317 @@ pub fn get_rows(conn: &Connection) -> Result<Vec<TablePathsEntry>, rusqlite::Err
         .collect()
 }
 
+pub fn get_state(conn: &Connection) -> Result<BTreeMap<String, String>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT key, value FROM state")?;
+    stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+}
+
+/// Returns when the `events` table started to record every note, if it hasn't always.
+pub fn get_events_complete_since(
+    conn: &Connection,
+) -> Result<Option<UnixTimestamp>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT CAST(value AS INTEGER) FROM state WHERE key = ?1",
+        params![EVENTS_COMPLETE_SINCE_KEY],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Records that notes before `timestamp` may be missing from the `events` table, unless that's
+/// already recorded for a later time.
+pub fn set_events_complete_since(
+    conn: &Connection,
+    timestamp: UnixTimestamp,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO state (key, value) VALUES (?1, CAST(?2 AS TEXT))
+         ON CONFLICT(key) DO UPDATE SET
+         value = CAST(MAX(CAST(value AS INTEGER), CAST(excluded.value AS INTEGER)) AS TEXT)",
+        params![EVENTS_COMPLETE_SINCE_KEY, timestamp],
+    )?;
+    Ok(())
+}
+
+/// Whether `key` is one of the `state` keys describing this database's own history, which mustn't
+/// be copied from another.
+pub fn is_history_state_key(key: &str) -> bool {
+    key == EVENTS_COMPLETE_SINCE_KEY
+}
+
+pub fn insert_event(
+    conn: &Connection,
+    path: &str,
+    timestamp: UnixTimestamp,
+    source: Option<&str>,
+    cwd: Option<&str>,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO events (path, timestamp, source, cwd) VALUES (?1, ?2, ?3, ?4)",
+        params![path, timestamp, source, cwd],
+    )?;
+    Ok(())
+}
+
+/// Maps paths given on the command line to the form they're stored under in the database, failing
+/// if any of them have never been noted.
+pub fn resolve_stored_paths(
+    conn: &Connection,
+    raw_paths: &[String],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let stored_paths: HashSet<String> = get_rows(conn)?.into_iter().map(|row| row.path).collect();
+    let mut resolved = Vec::new();
+    let mut unknown = Vec::new();
+
+    for raw_path in raw_paths {
+        match path::candidate_stored_paths(raw_path)
+            .into_iter()
+            .find(|candidate| stored_paths.contains(candidate))
+        {
+            Some(stored_path) => resolved.push(stored_path),
+            None => unknown.push(raw_path.as_str()),
+        }
+    }
+
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Not in the database, note first with 'memy note': {}",
+            unknown.join(", ")
+        )
+        .into());
+    }
+
+    Ok(resolved)
+}
+
+/// Runs the `SQLite` `integrity_check` pragma, returning the problems it found (none if the
+/// database is intact).
+pub fn integrity_check(conn: &Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+
+    Ok(messages
+        .into_iter()
+        .filter(|message| message != "ok")
+        .collect())
+}
+
+pub fn vacuum(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute("VACUUM", [])?;
+    Ok(())
+}
+
+/// Folds the entry for `duplicate` into `kept`, which should already hold the combined counts:
+/// `kept` is updated, and takes over the events and tags of `duplicate`, which is then removed.
+pub fn fold_path_into(
+    conn: &Connection,
+    duplicate: &str,
+    kept: &TablePathsEntry,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE paths SET noted_count = ?1, last_noted_timestamp = ?2, pinned = ?3 WHERE path = ?4",
+        params![
+            kept.noted_count,
+            kept.last_noted_timestamp,
+            kept.pinned,
+            kept.path
+        ],
+    )?;
+    conn.execute(
+        "UPDATE events SET path = ?1 WHERE path = ?2",
+        params![kept.path, duplicate],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO tags (path, tag) SELECT ?1, tag FROM tags WHERE path = ?2",
+        params![kept.path, duplicate],
+    )?;
+    conn.execute("DELETE FROM tags WHERE path = ?1", params![duplicate])?;
+    conn.execute("DELETE FROM paths WHERE path = ?1", params![duplicate])?;
+    Ok(())
+}
+
+/// Sets or clears the pinned flag on `paths`, returning how many of them are in the database.
+pub fn set_pinned(
+    conn: &Connection,
+    paths: &[String],
+    pinned: bool,
+) -> Result<usize, rusqlite::Error> {
+    let mut updated = 0;
+    for path in paths {
+        updated += conn.execute(
+            "UPDATE paths SET pinned = ?1 WHERE path = ?2",
+            params![pinned, path],
+        )?;
+    }
+    Ok(updated)
+}
+
+/// Adds `tag` to `paths`, returning how many didn't already have it.
+pub fn add_tag(conn: &Connection, paths: &[String], tag: &str) -> Result<usize, rusqlite::Error> {
+    let mut added = 0;
+    for path in paths {
+        added += conn.execute(
+            "INSERT OR IGNORE INTO tags (path, tag) VALUES (?1, ?2)",
+            params![path, tag],
+        )?;
+    }
+    Ok(added)
+}
+
+/// Removes `tag` from `paths`, returning how many had it.
+pub fn remove_tag(
+    conn: &Connection,
+    paths: &[String],
+    tag: &str,
+) -> Result<usize, rusqlite::Error> {
+    let mut removed = 0;
+    for path in paths {
+        removed += conn.execute(
+            "DELETE FROM tags WHERE path = ?1 AND tag = ?2",
+            params![path, tag],
+        )?;
+    }
+    Ok(removed)
+}
+
+/// Returns the tags on every tagged path.
+pub fn get_tags(conn: &Connection) -> Result<BTreeMap<String, BTreeSet<String>>, rusqlite::Error> {
+    let mut tags: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut stmt = conn.prepare("SELECT path, tag FROM tags")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    for row in rows {
+        let (path, tag): (String, String) = row?;
+        tags.entry(path).or_default().insert(tag);
+    }
+
+    Ok(tags)
+}
+
+/// Returns every recorded note, grouped by path.
+pub fn get_events(
+    conn: &Connection,
+) -> Result<HashMap<String, Vec<TableEventsEntry>>, rusqlite::Error> {
+    let mut events: HashMap<String, Vec<TableEventsEntry>> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT path, timestamp, source, cwd FROM events ORDER BY id")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            TableEventsEntry {
+                timestamp: row.get(1)?,
+                source: row.get(2)?,
+                cwd: row.get(3)?,
+            },
+        ))
+    })?;
+
+    for row in rows {
+        let (path, event) = row?;
+        events.entry(path).or_default().push(event);
+    }
+
+    Ok(events)
+}
+
+/// Returns every path noted at least once with one of `sources`.
+pub fn get_paths_noted_from(
+    conn: &Connection,
+    sources: &[String],
+) -> Result<HashSet<String>, rusqlite::Error> {
+    if sources.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let placeholders = sources.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT path FROM events WHERE source IN ({placeholders})"
+    ))?;
+    stmt.query_map(params_from_iter(sources), |row| row.get(0))?
+        .collect()
+}
+
+/// Returns the number of notes recorded between `start` (inclusive) and `end` (exclusive) for each
+/// source; notes without one are counted under `unknown`.
+pub fn get_note_counts_by_source(
+    conn: &Connection,
+    start: UnixTimestamp,
+    end: UnixTimestamp,
+) -> Result<BTreeMap<String, usize>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(source, 'unknown'), COUNT(*) FROM events
+         WHERE timestamp >= ?1 AND timestamp < ?2
+         GROUP BY COALESCE(source, 'unknown')",
+    )?;
+    stmt.query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+}
+
+/// Returns the time of every note made between `start` (inclusive) and `end` (exclusive).
+pub fn get_note_timestamps(
+    conn: &Connection,
+    start: UnixTimestamp,
+    end: UnixTimestamp,
+) -> Result<Vec<UnixTimestamp>, rusqlite::Error> {
+    let mut stmt =
+        conn.prepare("SELECT timestamp FROM events WHERE timestamp >= ?1 AND timestamp < ?2")?;
+    stmt.query_map(params![start, end], |row| row.get(0))?
+        .collect()
+}
+
+/// Returns a row for each path noted between `start` (inclusive) and `end` (exclusive), with the
+/// noted count and last noted timestamp covering only the notes in between.
+pub fn get_rows_noted_between(
+    conn: &Connection,
+    start: UnixTimestamp,
+    end: UnixTimestamp,
+) -> Result<Vec<TablePathsEntry>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT events.path AS path, COUNT(*) AS noted_count,
+                MAX(events.timestamp) AS last_noted_timestamp, paths.pinned AS pinned
+         FROM events JOIN paths ON paths.path = events.path
+         WHERE events.timestamp >= ?1 AND events.timestamp < ?2
+         GROUP BY events.path",
+    )?;
+    stmt.query_map(params![start, end], TablePathsEntry::from_row)?
+        .collect()
+}
+
+/// Returns how many paths were first noted between `start` (inclusive) and `end` (exclusive).
+pub fn count_paths_first_noted_between(
+    conn: &Connection,
+    start: UnixTimestamp,
+    end: UnixTimestamp,
+) -> Result<usize, rusqlite::Error> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM (
+             SELECT MIN(events.timestamp) AS first_noted
+             FROM events JOIN paths ON paths.path = events.path
+             GROUP BY events.path
+         ) WHERE first_noted >= ?1 AND first_noted < ?2",
+        params![start, end],
+        |row| row.get(0),
+    )
+}
+
+/// Removes `paths` from the database, including their event history and tags.
+pub fn delete_paths(conn: &Connection, paths: &[String]) -> Result<(), rusqlite::Error> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    let placeholders = paths.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+
+    for table in ["paths", "events", "tags"] {
+        let sql = format!("DELETE FROM {table} WHERE path IN ({placeholders})");
+        conn.execute(&sql, params_from_iter(paths))?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,4 +693,225 @@ mod tests {
         assert_eq!(get_db_version(&conn), DB_VERSION, "DB Version incorrect");
         close(conn).expect("Cannot close connection");
     }
+
+    #[test]
+    fn migrate_v2_to_v3_creates_synthetic_events() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        conn.execute(
+            "CREATE TABLE paths (
+                path TEXT PRIMARY KEY,
+                noted_count INTEGER NOT NULL,
+                last_noted_timestamp INTEGER NOT NULL
+            )",
+            [],
+        )
+        .expect("Failed to create paths table");
+        conn.execute(
+            "INSERT INTO paths VALUES ('/a', 3, 100), ('/b', 1, 200)",
+            [],
+        )
+        .expect("Failed to insert rows");
+        create_state_table(&conn, 0);
+
+        migrate_v2_to_v3(&conn);
+
+        let events: Vec<(String, UnixTimestamp)> = conn
+            .prepare("SELECT path, timestamp FROM events ORDER BY path")
+            .expect("Select failed")
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("Query mapping failed")
+            .collect::<Result<_, _>>()
+            .expect("Reading events failed");
+
+        assert_eq!(
+            events,
+            vec![("/a".to_owned(), 100), ("/b".to_owned(), 200)],
+            "Expected one synthetic event per existing row"
+        );
+        assert!(
+            get_events_complete_since(&conn)
+                .expect("Reading state failed")
+                .is_some_and(|since| since > 200),
+            "Events should only be complete from the migration on"
+        );
+        assert_eq!(get_db_version(&conn), 3, "DB Version incorrect");
+    }
+
+    #[test]
+    fn set_events_complete_since_keeps_latest() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        init_db(&conn);
+        assert_eq!(
+            get_events_complete_since(&conn).expect("Reading state failed"),
+            None,
+            "A new database has every note"
+        );
+
+        for timestamp in [200, 100] {
+            set_events_complete_since(&conn, timestamp).expect("Writing state failed");
+        }
+        assert_eq!(
+            get_events_complete_since(&conn).expect("Reading state failed"),
+            Some(200),
+            "An earlier time shouldn't replace a later one"
+        );
+    }
+
+    #[test]
+    fn interrupted_migration_is_rolled_back() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        conn.execute(
+            "CREATE TABLE paths (
+                path TEXT PRIMARY KEY,
+                noted_count INTEGER NOT NULL,
+                last_noted_timestamp INTEGER NOT NULL
+            )",
+            [],
+        )
+        .expect("Failed to create paths table");
+        create_state_table(&conn, 0);
+        conn.execute("PRAGMA user_version = 2;", [])
+            .expect("Failed to set database version");
+
+        let interrupted = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            migrate_in_transaction(&conn, |tx| {
+                create_events_table(tx);
+                panic!("Interrupted before the version was bumped");
+            })
+        }));
+        assert!(interrupted.is_err(), "The migration should have panicked");
+        assert_eq!(get_db_version(&conn), 2, "DB Version should be unchanged");
+
+        check_version_and_migrate(&conn).expect("Migrating again should succeed");
+        assert_eq!(get_db_version(&conn), DB_VERSION, "DB Version incorrect");
+    }
+
+    #[test]
+    fn delete_paths_removes_events() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        init_db(&conn);
+        conn.execute(
+            "INSERT INTO paths (path, noted_count, last_noted_timestamp) VALUES ('/a', 1, 100)",
+            [],
+        )
+        .expect("Failed to insert row");
+        insert_event(&conn, "/a", 100, None, None).expect("Failed to insert event");
+
+        delete_paths(&conn, &["/a".to_owned()]).expect("Failed to delete paths");
+
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .expect("Count failed");
+        assert_eq!(remaining, 0, "Events should be deleted along with the path");
+    }
+
+    #[test]
+    fn migrate_v3_to_v4_leaves_paths_unpinned() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        conn.execute(
+            "CREATE TABLE paths (
+                path TEXT PRIMARY KEY,
+                noted_count INTEGER NOT NULL,
+                last_noted_timestamp INTEGER NOT NULL
+            )",
+            [],
+        )
+        .expect("Failed to create paths table");
+        conn.execute("INSERT INTO paths VALUES ('/a', 3, 100)", [])
+            .expect("Failed to insert row");
+
+        migrate_v3_to_v4(&conn);
+
+        let rows = get_rows(&conn).expect("Failed to read rows");
+        assert_eq!(rows.len(), 1);
+        assert!(!rows[0].pinned, "Existing paths should not be pinned");
+    }
+
+    #[test]
+    fn set_pinned_counts_known_paths() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        init_db(&conn);
+        conn.execute(
+            "INSERT INTO paths (path, noted_count, last_noted_timestamp) VALUES ('/a', 1, 100)",
+            [],
+        )
+        .expect("Failed to insert row");
+
+        let updated = set_pinned(&conn, &["/a".to_owned(), "/missing".to_owned()], true)
+            .expect("Failed to pin");
+        assert_eq!(updated, 1);
+        assert!(get_rows(&conn).expect("Failed to read rows")[0].pinned);
+
+        set_pinned(&conn, &["/a".to_owned()], false).expect("Failed to unpin");
+        assert!(!get_rows(&conn).expect("Failed to read rows")[0].pinned);
+    }
+
+    #[test]
+    fn tags_are_added_removed_and_forgotten() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        init_db(&conn);
+        let paths = ["/a".to_owned(), "/b".to_owned()];
+
+        assert_eq!(add_tag(&conn, &paths, "docs").expect("Failed to tag"), 2);
+        assert_eq!(
+            add_tag(&conn, &paths[..1], "docs").expect("Failed to tag"),
+            0
+        );
+        add_tag(&conn, &paths[..1], "infra").expect("Failed to tag");
+
+        let tags = get_tags(&conn).expect("Failed to read tags");
+        assert_eq!(
+            tags.get("/a")
+                .map(|t| t.iter().cloned().collect::<Vec<_>>()),
+            Some(vec!["docs".to_owned(), "infra".to_owned()])
+        );
+
+        assert_eq!(
+            remove_tag(&conn, &paths, "infra").expect("Failed to untag"),
+            1
+        );
+        delete_paths(&conn, &paths[1..]).expect("Failed to delete paths");
+
+        let remaining = get_tags(&conn).expect("Failed to read tags");
+        assert_eq!(remaining.len(), 1, "Forgotten paths should lose their tags");
+        assert!(remaining.contains_key("/a"));
+    }
+
+    #[test]
+    fn fold_path_into_moves_events_and_tags() {
+        let conn = Connection::open_in_memory().expect("Could not open connection");
+        init_db(&conn);
+        conn.execute(
+            "INSERT INTO paths (path, noted_count, last_noted_timestamp)
+             VALUES ('/real', 2, 100), ('/link', 3, 200)",
+            [],
+        )
+        .expect("Failed to insert rows");
+        insert_event(&conn, "/link", 200, None, None).expect("Failed to insert event");
+        add_tag(&conn, &["/link".to_owned()], "docs").expect("Failed to tag");
+
+        let kept = TablePathsEntry {
+            path: "/real".to_owned(),
+            noted_count: 5,
+            last_noted_timestamp: 200,
+            pinned: false,
+        };
+        fold_path_into(&conn, "/link", &kept).expect("Failed to fold path");
+
+        assert_eq!(get_rows(&conn).expect("Failed to read rows"), vec![kept]);
+        let event_path: String = conn
+            .query_row("SELECT path FROM events", [], |row| row.get(0))
+            .expect("Event missing");
+        assert_eq!(event_path, "/real");
+        assert!(
+            get_tags(&conn)
+                .expect("Failed to read tags")
+                .contains_key("/real")
+        );
+        assert!(
+            integrity_check(&conn)
+                .expect("Integrity check failed")
+                .is_empty()
+        );
+    }
 }
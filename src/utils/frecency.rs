@@ -1,42 +1,226 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
 use super::config;
-use super::types::{Frecency, NotedCount, UnixTimestampHours};
+use super::config::FrecencyAlgorithm;
+use super::types::{Frecency, NotedCount, UnixTimestamp, UnixTimestampHours};
 
-fn calculate_with_lambda(
-    count: NotedCount,
-    last_noted_timestamp_hours: UnixTimestampHours,
-    highest_count: NotedCount,
-    oldest_last_noted_timestamp_hours: UnixTimestampHours,
-    lambda: f64,
-) -> Frecency {
-    let freq_score = if highest_count > 0 {
+const HOUR: UnixTimestampHours = 1.0;
+const DAY: UnixTimestampHours = 24.0 * HOUR;
+const WEEK: UnixTimestampHours = 7.0 * DAY;
+
+/// Everything a [`Scorer`] knows about a path. Ages are in hours before now.
+pub struct FrecencyInput {
+    pub count: NotedCount,
+    pub last_noted_age_hours: UnixTimestampHours,
+    pub highest_count: NotedCount,
+    pub oldest_last_noted_age_hours: UnixTimestampHours,
+}
+
+/// A frecency scoring algorithm. Every scorer returns a value between 0 and 1, where 1 is the
+/// score a path with the highest count in the database would get if it had just been noted.
+pub trait Scorer: Send + Sync {
+    fn score(&self, input: &FrecencyInput) -> Frecency;
+}
+
+/// Linear blend of normalised frequency and recency, weighted by `recency_bias`.
+pub struct Linear {
+    pub lambda: f64,
+}
+
+/// zoxide's algorithm: the count is multiplied by a factor depending on which age bucket the
+/// last note falls into.
+pub struct ZoxideBuckets;
+
+/// fasd's algorithm: fasd's rank grows by `1/rank` on each note (roughly `sqrt(2n)`), and is
+/// multiplied by a factor depending on which age bucket the last note falls into.
+pub struct FasdRank;
+
+/// The count decays exponentially with the age of the last note, halving every `half_life_hours`.
+pub struct HalfLife {
+    pub half_life_hours: UnixTimestampHours,
+}
+
+/// How often a path was noted, relative to the most noted path: between 0 and 1.
+#[must_use]
+pub fn normalised_frequency(count: NotedCount, highest_count: NotedCount) -> f64 {
+    if highest_count > 0 {
         count as f64 / highest_count as f64
     } else {
         0.0
-    };
+    }
+}
 
-    let recency_score = if last_noted_timestamp_hours < oldest_last_noted_timestamp_hours {
-        1.0 - (last_noted_timestamp_hours / oldest_last_noted_timestamp_hours)
+/// How recently a path was noted, relative to the oldest note: 1 for just now, falling to 0 for
+/// the oldest.
+#[must_use]
+pub fn normalised_recency(
+    last_noted_age_hours: UnixTimestampHours,
+    oldest_last_noted_age_hours: UnixTimestampHours,
+) -> f64 {
+    if last_noted_age_hours < oldest_last_noted_age_hours {
+        1.0 - (last_noted_age_hours / oldest_last_noted_age_hours)
     } else {
         0.0
-    };
-
-    (1.0 - lambda).mul_add(freq_score, lambda * recency_score)
+    }
 }
 
-pub fn calculate(
+fn calculate_with_lambda(
     count: NotedCount,
     last_noted_timestamp_hours: UnixTimestampHours,
     highest_count: NotedCount,
     oldest_last_noted_timestamp_hours: UnixTimestampHours,
+    lambda: f64,
 ) -> Frecency {
-    let lambda = config::get_recency_bias();
-    calculate_with_lambda(
-        count,
+    let freq_score = normalised_frequency(count, highest_count);
+    let recency_score = normalised_recency(
         last_noted_timestamp_hours,
-        highest_count,
         oldest_last_noted_timestamp_hours,
-        lambda,
-    )
+    );
+
+    (1.0 - lambda).mul_add(freq_score, lambda * recency_score)
+}
+
+impl Scorer for Linear {
+    fn score(&self, input: &FrecencyInput) -> Frecency {
+        calculate_with_lambda(
+            input.count,
+            input.last_noted_age_hours,
+            input.highest_count,
+            input.oldest_last_noted_age_hours,
+            self.lambda,
+        )
+    }
+}
+
+impl Scorer for ZoxideBuckets {
+    fn score(&self, input: &FrecencyInput) -> Frecency {
+        const MAX_MULTIPLIER: f64 = 4.0;
+
+        if input.highest_count == 0 {
+            return 0.0;
+        }
+
+        let multiplier = match input.last_noted_age_hours {
+            age if age < HOUR => MAX_MULTIPLIER,
+            age if age < DAY => 2.0,
+            age if age < WEEK => 0.5,
+            _ => 0.25,
+        };
+
+        (input.count as f64 * multiplier) / (input.highest_count as f64 * MAX_MULTIPLIER)
+    }
+}
+
+fn fasd_rank(count: NotedCount) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        (2.0_f64.mul_add(count as f64, -1.0)).sqrt()
+    }
+}
+
+impl Scorer for FasdRank {
+    fn score(&self, input: &FrecencyInput) -> Frecency {
+        const MAX_MULTIPLIER: f64 = 6.0;
+
+        if input.highest_count == 0 {
+            return 0.0;
+        }
+
+        let multiplier = match input.last_noted_age_hours {
+            age if age < HOUR => MAX_MULTIPLIER,
+            age if age < DAY => 4.0,
+            age if age < WEEK => 2.0,
+            _ => 1.0,
+        };
+
+        (fasd_rank(input.count) * multiplier) / (fasd_rank(input.highest_count) * MAX_MULTIPLIER)
+    }
+}
+
+impl Scorer for HalfLife {
+    fn score(&self, input: &FrecencyInput) -> Frecency {
+        if input.highest_count == 0 {
+            return 0.0;
+        }
+
+        let decay = 0.5_f64.powf(input.last_noted_age_hours.max(0.0) / self.half_life_hours);
+        input.count as f64 * decay / input.highest_count as f64
+    }
+}
+
+/// Returns the scorer selected by the `frecency_algorithm` config option.
+pub fn get_scorer() -> Box<dyn Scorer> {
+    match config::get_frecency_algorithm() {
+        FrecencyAlgorithm::Linear => Box::new(Linear {
+            lambda: config::get_recency_bias(),
+        }),
+        FrecencyAlgorithm::Zoxide => Box::new(ZoxideBuckets),
+        FrecencyAlgorithm::Fasd => Box::new(FasdRank),
+        FrecencyAlgorithm::HalfLife => Box::new(HalfLife {
+            half_life_hours: config::get_frecency_half_life_days() * DAY,
+        }),
+    }
+}
+
+/// Returns the factor to multiply the frecency of `path` by when listing from `cwd`: the full
+/// `boost` for paths at or under `cwd`, with the bonus over 1 halving for every level of `cwd` that
+/// `path` doesn't share.
+#[must_use]
+pub fn cwd_multiplier(path: &Path, cwd: &Path, boost: f64) -> f64 {
+    let is_normal = |component: &Component| matches!(component, Component::Normal(_));
+
+    let cwd_depth = cwd.components().filter(is_normal).count();
+    let shared_depth = path
+        .components()
+        .zip(cwd.components())
+        .take_while(|(path_component, cwd_component)| path_component == cwd_component)
+        .filter(|(path_component, _)| is_normal(path_component))
+        .count();
+
+    let distance = i32::try_from(cwd_depth - shared_depth).unwrap_or(i32::MAX);
+    (boost - 1.0).mul_add(0.5_f64.powi(distance), 1.0)
+}
+
+/// Frecency a directory has gained from the files below it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivedFrecency {
+    pub frecency: Frecency,
+    pub last_noted_timestamp: UnixTimestamp,
+}
+
+/// Spreads the frecency of each file to the directories above it, multiplied by `decay` for each
+/// level up, so directories full of frequently used files rank well without being noted
+/// themselves. Nothing flows to `stop_at` (typically the home directory), the directories above
+/// it, or the filesystem root, as they'd otherwise collect the frecency of everything.
+#[must_use]
+pub fn derive_dir_frecencies<'a>(
+    files: impl IntoIterator<Item = (&'a Path, Frecency, UnixTimestamp)>,
+    decay: f64,
+    stop_at: Option<&Path>,
+) -> HashMap<PathBuf, DerivedFrecency> {
+    let mut derived: HashMap<PathBuf, DerivedFrecency> = HashMap::new();
+
+    for (file, frecency, last_noted_timestamp) in files {
+        let mut contribution = frecency;
+
+        for dir in file.ancestors().skip(1) {
+            if dir.parent().is_none() || stop_at.is_some_and(|stop| stop.starts_with(dir)) {
+                break;
+            }
+
+            contribution *= decay;
+            let entry = derived.entry(dir.to_path_buf()).or_insert(DerivedFrecency {
+                frecency: 0.0,
+                last_noted_timestamp,
+            });
+            entry.frecency += contribution;
+            entry.last_noted_timestamp = entry.last_noted_timestamp.max(last_noted_timestamp);
+        }
+    }
+
+    derived
 }
 
 #[allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
@@ -49,6 +233,56 @@ mod tests {
     use super::*;
     use proptest::prelude::*;
 
+    #[test]
+    fn test_derive_dir_frecencies_decays_per_level() {
+        let derived = derive_dir_frecencies(
+            [(Path::new("/home/user/src/memy/main.rs"), 1.0, 100)],
+            0.5,
+            Some(Path::new("/home/user")),
+        );
+
+        assert_eq!(derived.len(), 2);
+        assert_eq!(derived[Path::new("/home/user/src/memy")].frecency, 0.5);
+        assert_eq!(derived[Path::new("/home/user/src")].frecency, 0.25);
+        assert_eq!(
+            derived[Path::new("/home/user/src")].last_noted_timestamp,
+            100
+        );
+    }
+
+    #[test]
+    fn test_derive_dir_frecencies_sums_files() {
+        let derived = derive_dir_frecencies(
+            [
+                (Path::new("/work/memy/a.rs"), 0.5, 100),
+                (Path::new("/work/memy/b.rs"), 0.25, 200),
+            ],
+            1.0,
+            None,
+        );
+
+        assert_eq!(
+            derived[Path::new("/work/memy")],
+            DerivedFrecency {
+                frecency: 0.75,
+                last_noted_timestamp: 200
+            }
+        );
+        assert_eq!(derived[Path::new("/work")].frecency, 0.75);
+        assert!(!derived.contains_key(Path::new("/")), "Root never derives");
+    }
+
+    #[test]
+    fn test_derive_dir_frecencies_nothing_above_stop() {
+        let derived = derive_dir_frecencies(
+            [(Path::new("/home/user/notes.txt"), 1.0, 100)],
+            0.5,
+            Some(Path::new("/home/user")),
+        );
+
+        assert!(derived.is_empty());
+    }
+
     #[test]
     fn test_zero_highest_count_zeros_freq_component() {
         // When highest_count = 0, freq_score = 0; with lambda=0, full result is 0
@@ -82,6 +316,156 @@ mod tests {
         assert!((result - 1.0).abs() < f64::EPSILON);
     }
 
+    fn input(count: NotedCount, age_hours: f64, highest_count: NotedCount) -> FrecencyInput {
+        FrecencyInput {
+            count,
+            last_noted_age_hours: age_hours,
+            highest_count,
+            oldest_last_noted_age_hours: 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_zoxide_buckets() {
+        assert!((ZoxideBuckets.score(&input(10, 0.5, 10)) - 1.0).abs() < f64::EPSILON);
+        assert!((ZoxideBuckets.score(&input(10, 2.0, 10)) - 0.5).abs() < f64::EPSILON);
+        assert!((ZoxideBuckets.score(&input(10, 48.0, 10)) - 0.125).abs() < f64::EPSILON);
+        assert!((ZoxideBuckets.score(&input(10, 500.0, 10)) - 0.0625).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_fasd_rank() {
+        assert!((fasd_rank(1) - 1.0).abs() < f64::EPSILON);
+        assert!((FasdRank.score(&input(10, 0.5, 10)) - 1.0).abs() < f64::EPSILON);
+        assert!((FasdRank.score(&input(10, 500.0, 10)) - 1.0 / 6.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_half_life_halves_after_one_half_life() {
+        let scorer = HalfLife {
+            half_life_hours: 24.0,
+        };
+        assert!((scorer.score(&input(10, 0.0, 10)) - 1.0).abs() < f64::EPSILON);
+        assert!((scorer.score(&input(10, 24.0, 10)) - 0.5).abs() < f64::EPSILON);
+        assert!((scorer.score(&input(10, 48.0, 10)) - 0.25).abs() < f64::EPSILON);
+    }
+
+    fn all_scorers(lambda: f64, half_life_hours: f64) -> Vec<Box<dyn Scorer>> {
+        vec![
+            Box::new(Linear { lambda }),
+            Box::new(ZoxideBuckets),
+            Box::new(FasdRank),
+            Box::new(HalfLife { half_life_hours }),
+        ]
+    }
+
+    #[test]
+    fn test_cwd_multiplier_under_cwd() {
+        let cwd = Path::new("/home/user/project");
+        assert!(
+            (cwd_multiplier(Path::new("/home/user/project"), cwd, 3.0) - 3.0).abs() < f64::EPSILON
+        );
+        assert!(
+            (cwd_multiplier(Path::new("/home/user/project/src/main.rs"), cwd, 3.0) - 3.0).abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_cwd_multiplier_near_cwd() {
+        let cwd = Path::new("/home/user/project");
+        // Sibling of the cwd: one level away
+        let sibling = cwd_multiplier(Path::new("/home/user/other"), cwd, 5.0);
+        assert!((sibling - 3.0).abs() < f64::EPSILON, "got {sibling}");
+
+        // Two levels away
+        let cousin = cwd_multiplier(Path::new("/home/other/file"), cwd, 5.0);
+        assert!((cousin - 2.0).abs() < f64::EPSILON, "got {cousin}");
+    }
+
+    #[test]
+    fn test_cwd_multiplier_unrelated() {
+        let cwd = Path::new("/home/user/project");
+        let unrelated = cwd_multiplier(Path::new("/etc/hosts"), cwd, 9.0);
+        assert!((unrelated - 2.0).abs() < f64::EPSILON, "got {unrelated}");
+        // A similar-looking prefix isn't a shared component
+        let similar = cwd_multiplier(Path::new("/home/user/project2"), cwd, 9.0);
+        assert!((similar - 5.0).abs() < f64::EPSILON, "got {similar}");
+    }
+
+    #[test]
+    fn test_cwd_multiplier_root_cwd() {
+        assert!(
+            (cwd_multiplier(Path::new("/etc/hosts"), Path::new("/"), 2.0) - 2.0).abs()
+                < f64::EPSILON
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_cwd_multiplier_between_one_and_boost(
+            path_components in prop::collection::vec("[a-c]{1,2}", 0..6),
+            cwd_components in prop::collection::vec("[a-c]{1,2}", 0..6),
+            boost in 1.0f64..10.0f64,
+        ) {
+            let path = format!("/{}", path_components.join("/"));
+            let cwd = format!("/{}", cwd_components.join("/"));
+            let result = cwd_multiplier(Path::new(&path), Path::new(&cwd), boost);
+            prop_assert!(result >= 1.0 - f64::EPSILON, "multiplier {result} should be >= 1");
+            prop_assert!(result <= boost + f64::EPSILON, "multiplier {result} should be <= {boost}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_all_scorers_in_unit_range(
+            count in 0u64..=1000u64,
+            extra in 0u64..=1000u64,
+            last_noted_hours in 0.0f64..999.0f64,
+            lambda in 0.0f64..=1.0f64,
+            half_life_hours in 1.0f64..10_000.0f64,
+        ) {
+            let scorer_input = input(count, last_noted_hours, count + extra);
+            for scorer in all_scorers(lambda, half_life_hours) {
+                let result = scorer.score(&scorer_input);
+                prop_assert!((0.0..=1.0).contains(&result), "frecency {result} should be in [0, 1]");
+            }
+        }
+
+        #[test]
+        fn prop_count_based_scorers_prefer_higher_count(
+            count1 in 1u64..=499u64,
+            diff in 1u64..=500u64,
+            last_noted_hours in 0.0f64..999.0f64,
+            half_life_hours in 1.0f64..10_000.0f64,
+        ) {
+            let count2 = count1 + diff;
+            for scorer in all_scorers(0.0, half_life_hours) {
+                let result1 = scorer.score(&input(count1, last_noted_hours, count2));
+                let result2 = scorer.score(&input(count2, last_noted_hours, count2));
+                prop_assert!(result2 > result1,
+                    "count2={count2} should score higher than count1={count1}");
+            }
+        }
+
+        #[test]
+        fn prop_scorers_never_prefer_older_notes(
+            count in 1u64..=1000u64,
+            hours_recent in 0.0f64..500.0f64,
+            extra_hours in 0.0f64..500.0f64,
+            lambda in 0.0f64..=1.0f64,
+            half_life_hours in 1.0f64..10_000.0f64,
+        ) {
+            let hours_older = hours_recent + extra_hours;
+            for scorer in all_scorers(lambda, half_life_hours) {
+                let result_recent = scorer.score(&input(count, hours_recent, count));
+                let result_older = scorer.score(&input(count, hours_older, count));
+                prop_assert!(result_recent >= result_older,
+                    "hours_recent={hours_recent} should score at least as high as hours_older={hours_older}");
+            }
+        }
+    }
+
     proptest! {
         #[test]
         fn prop_frecency_always_in_unit_range(
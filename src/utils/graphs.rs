@@ -1,9 +1,17 @@
+use colored::Colorize as _;
 use core::fmt::Write as _;
 use terminal_size::{Width, terminal_size};
 
 const BAR_CHAR: &str = "█";
 const MAX_CHART_HEIGHT: usize = 10;
 
+/// Heatmap cells from no activity up to the busiest day.
+const HEATMAP_SHADES: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+const HEATMAP_WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+/// Each week takes a shaded cell and a space, so the cells look roughly square.
+const HEATMAP_CELL_WIDTH: usize = 2;
+const HEATMAP_Y_AXIS_WIDTH: usize = 4;
+
 pub const COL_WIDTH: usize = 9;
 
 pub fn get_terminal_width() -> usize {
@@ -41,26 +49,53 @@ const fn nice_step(max_val: usize, max_rows: usize) -> usize {
 }
 
 pub fn render_bar_chart(title: &str, entries: &[(String, usize)], terminal_width: usize) -> String {
+    let weighted: Vec<(String, f64)> = entries
+        .iter()
+        .map(|(label, count)| (label.clone(), *count as f64))
+        .collect();
+    render_weighted_bar_chart(title, &weighted, terminal_width)
+}
+
+/// Like [`render_bar_chart`], for fractional values such as summed frecency scores. Values are
+/// printed as given, so round them first.
+pub fn render_weighted_bar_chart(
+    title: &str,
+    entries: &[(String, f64)],
+    terminal_width: usize,
+) -> String {
     if entries.is_empty() {
         return String::new();
     }
 
-    let max_count = entries.iter().map(|(_, c)| *c).max().unwrap_or(1);
+    let max_value = entries.iter().map(|(_, v)| *v).fold(0.0, f64::max);
     let max_label_len = entries.iter().map(|(l, _)| l.len()).max().unwrap_or(1);
-    let count_digits = max_count.to_string().len();
 
-    // Layout: "LABEL │ BAR COUNT\n"
-    // Overhead: max_label_len + 1 (space) + 1 (│) + 1 (space) + 1 (space) + count_digits
-    let overhead = max_label_len + 4 + 1 + count_digits;
+    // Layout: "LABEL │ BAR VALUE\n"
+    // Overhead: max_label_len + 1 (space) + 1 (│) + 1 (space) + 1 (space) + value_width
+    let value_width = entries
+        .iter()
+        .map(|(_, v)| v.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let overhead = max_label_len + 4 + 1 + value_width;
     let bar_width = terminal_width.saturating_sub(overhead).max(5);
 
     let mut output = format!("{title}:\n");
-    for (label, count) in entries {
-        let bar_len = (count * bar_width).checked_div(max_count).unwrap_or(0);
+    for (label, value) in entries {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "Between 0 and bar_width"
+        )]
+        let bar_len = if max_value > 0.0 {
+            (value * bar_width as f64 / max_value) as usize
+        } else {
+            0
+        };
         let bar = BAR_CHAR.repeat(bar_len);
         let _ = writeln!(
             output,
-            "{label:<max_label_len$} │ {bar:<bar_width$} {count}"
+            "{label:<max_label_len$} │ {bar:<bar_width$} {value}"
         );
     }
 
@@ -147,6 +182,96 @@ pub fn render_column_chart(
     output
 }
 
+/// One column of a [`render_heatmap`]: a week, Monday first.
+pub struct HeatmapWeek {
+    /// Shown above the week, usually the month it starts.
+    pub label: Option<String>,
+    /// `None` for days outside the period shown, such as those after today.
+    pub days: [Option<usize>; 7],
+}
+
+/// Which of [`HEATMAP_SHADES`] to draw `count` with, where `max_count` gets the darkest.
+const fn heatmap_level(count: usize, max_count: usize) -> usize {
+    if count == 0 || max_count == 0 {
+        0
+    } else {
+        let level = (count * (HEATMAP_SHADES.len() - 1)).div_ceil(max_count);
+        if level > HEATMAP_SHADES.len() - 1 {
+            HEATMAP_SHADES.len() - 1
+        } else {
+            level
+        }
+    }
+}
+
+fn heatmap_cell(level: usize) -> String {
+    let shade = HEATMAP_SHADES[level];
+    if level == 0 {
+        shade.dimmed().to_string()
+    } else {
+        shade.green().to_string()
+    }
+}
+
+/// Renders a GitHub-style calendar heatmap, a row for each weekday and a column for each week,
+/// keeping as many of the latest `weeks` as fit in `terminal_width`.
+pub fn render_heatmap(title: &str, weeks: &[HeatmapWeek], terminal_width: usize) -> String {
+    if weeks.is_empty() {
+        return String::new();
+    }
+
+    let max_weeks =
+        (terminal_width.saturating_sub(HEATMAP_Y_AXIS_WIDTH) / HEATMAP_CELL_WIDTH).max(1);
+    let shown = &weeks[weeks.len().saturating_sub(max_weeks)..];
+    let max_count = shown
+        .iter()
+        .flat_map(|week| week.days.iter().flatten())
+        .copied()
+        .max()
+        .unwrap_or(0);
+
+    let mut output = format!("{title}:\n");
+
+    // Labels are skipped where they would run into the one before
+    let mut label_row = " ".repeat(HEATMAP_Y_AXIS_WIDTH);
+    for (idx, week) in shown.iter().enumerate() {
+        let column = HEATMAP_Y_AXIS_WIDTH + idx * HEATMAP_CELL_WIDTH;
+        if let Some(label) = &week.label {
+            let row_len = label_row.chars().count();
+            let gap = usize::from(row_len > HEATMAP_Y_AXIS_WIDTH);
+            if row_len + gap <= column {
+                label_row.push_str(&" ".repeat(column - row_len));
+                label_row.push_str(label);
+            }
+        }
+    }
+    let _ = writeln!(output, "{}", label_row.trim_end());
+
+    for (weekday, name) in HEATMAP_WEEKDAYS.iter().enumerate() {
+        let _ = write!(output, "{name:<HEATMAP_Y_AXIS_WIDTH$}");
+        let mut row = String::new();
+        for week in shown {
+            match week.days[weekday] {
+                Some(count) => {
+                    let _ = write!(row, "{} ", heatmap_cell(heatmap_level(count, max_count)));
+                }
+                None => row.push_str(&" ".repeat(HEATMAP_CELL_WIDTH)),
+            }
+        }
+        let _ = writeln!(output, "{}", row.trim_end());
+    }
+
+    let legend: Vec<String> = (0..HEATMAP_SHADES.len()).map(heatmap_cell).collect();
+    let _ = writeln!(
+        output,
+        "{}Less {} More (busiest day: {max_count})",
+        " ".repeat(HEATMAP_Y_AXIS_WIDTH),
+        legend.join(" ")
+    );
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +318,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_heatmap_level() {
+        assert_eq!(heatmap_level(0, 10), 0);
+        assert_eq!(heatmap_level(1, 10), 1);
+        assert_eq!(heatmap_level(3, 10), 2);
+        assert_eq!(heatmap_level(8, 10), 4);
+        assert_eq!(heatmap_level(10, 10), 4);
+        assert_eq!(heatmap_level(1, 1), 4);
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        colored::control::set_override(false);
+
+        let weeks = vec![
+            HeatmapWeek {
+                label: Some("Jan".to_owned()),
+                days: [
+                    Some(0),
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    Some(4),
+                    Some(0),
+                    Some(0),
+                ],
+            },
+            HeatmapWeek {
+                label: Some("Feb".to_owned()),
+                days: [Some(4), Some(0), None, None, None, None, None],
+            },
+            HeatmapWeek {
+                label: None,
+                days: [None; 7],
+            },
+        ];
+        let output = render_heatmap("Notes by Day", &weeks, 80);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "Notes by Day:");
+        assert_eq!(lines[1], "    Jan", "Feb would touch Jan, so it's skipped");
+        assert_eq!(lines[2], "Mon · █");
+        assert_eq!(lines[3], "Tue ░ ·");
+        assert_eq!(lines[4], "Wed ▒");
+        assert_eq!(lines[6], "Fri █");
+        assert_eq!(lines[8], "Sun ·");
+        assert!(lines[9].contains("Less · ░ ▒ ▓ █ More (busiest day: 4)"));
+    }
+
+    #[test]
+    fn test_render_heatmap_keeps_latest_weeks_that_fit() {
+        colored::control::set_override(false);
+
+        // Only the earlier weeks are busy
+        let weeks: Vec<HeatmapWeek> = (1..=20)
+            .map(|week| HeatmapWeek {
+                label: None,
+                days: [Some(if week <= 15 { 100 } else { 20 }); 7],
+            })
+            .collect();
+        let output = render_heatmap("Notes by Day", &weeks, 14);
+
+        // (14 - 4) / 2 = 5 weeks: the last five
+        assert_eq!(output.lines().nth(2), Some("Mon █ █ █ █ █"));
+        assert!(output.contains("(busiest day: 20)"));
+        assert!(render_heatmap("Empty", &[], 80).is_empty());
+    }
+
+    #[test]
+    fn test_render_weighted_bar_chart() {
+        let entries = vec![("a".to_owned(), 1.5), ("b".to_owned(), 0.75)];
+        let output = render_weighted_bar_chart("Weights", &entries, 20);
+        assert_eq!(
+            output,
+            "Weights:\na │ ██████████ 1.5\nb │ █████      0.75\n"
+        );
+    }
+
     #[test]
     fn test_render_bar_chart_no_bottom_line() {
         let entries = vec![("1".to_owned(), 3), ("2-3".to_owned(), 2)];
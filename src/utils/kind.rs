@@ -0,0 +1,188 @@
+use std::fs::File;
+use std::io::Read as _;
+use std::path::Path;
+
+use super::cli::FileKind;
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "avif", "bmp", "gif", "heic", "heif", "ico", "jpeg", "jpg", "jxl", "png", "psd", "raw", "svg",
+    "tif", "tiff", "webp",
+];
+
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "3gp", "avi", "flv", "m4v", "mkv", "mov", "mp4", "mpeg", "mpg", "ogv", "webm", "wmv",
+];
+
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "adoc", "doc", "docx", "epub", "md", "markdown", "odp", "ods", "odt", "org", "pdf", "ppt",
+    "pptx", "rst", "rtf", "tex", "txt", "xls", "xlsx",
+];
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "bash", "c", "cc", "clj", "cpp", "cs", "css", "cxx", "dart", "el", "erl", "ex", "exs", "fish",
+    "go", "h", "hpp", "hs", "html", "ini", "java", "js", "json", "jsx", "kt", "lua", "mjs", "ml",
+    "nix", "php", "pl", "py", "r", "rb", "rs", "scala", "scss", "sh", "sql", "swift", "toml", "ts",
+    "tsx", "vim", "vue", "xml", "yaml", "yml", "zig", "zsh",
+];
+
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "7z", "bz2", "deb", "dmg", "gz", "iso", "jar", "lz4", "rar", "rpm", "tar", "tbz2", "tgz", "xz",
+    "zip", "zst",
+];
+
+/// How many bytes from the start of a file [`sniff_kind`] needs, enough to reach the tar magic.
+const SNIFF_LENGTH: usize = 262;
+
+/// Returns `path`'s extension, lowercased.
+fn extension(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+/// Returns the kind of file `path` is, judged by its extension.
+#[must_use]
+pub fn kind_from_extension(path: &str) -> Option<FileKind> {
+    let ext = extension(path)?;
+    [
+        (FileKind::Image, IMAGE_EXTENSIONS),
+        (FileKind::Video, VIDEO_EXTENSIONS),
+        (FileKind::Document, DOCUMENT_EXTENSIONS),
+        (FileKind::Source, SOURCE_EXTENSIONS),
+        (FileKind::Archive, ARCHIVE_EXTENSIONS),
+    ]
+    .into_iter()
+    .find_map(|(kind, extensions)| extensions.contains(&ext.as_str()).then_some(kind))
+}
+
+/// Recognises common formats from the first bytes of a file.
+fn kind_from_magic(header: &[u8]) -> Option<FileKind> {
+    let at = |offset: usize, magic: &[u8]| header.get(offset..offset + magic.len()) == Some(magic);
+
+    if at(0, b"\x89PNG")
+        || at(0, b"\xFF\xD8\xFF")
+        || at(0, b"GIF8")
+        || at(0, b"II*\0")
+        || at(0, b"MM\0*")
+        || (at(0, b"RIFF") && at(8, b"WEBP"))
+        || (at(4, b"ftyp") && (at(8, b"heic") || at(8, b"avif")))
+    {
+        Some(FileKind::Image)
+    } else if at(4, b"ftyp") || at(0, b"\x1A\x45\xDF\xA3") || (at(0, b"RIFF") && at(8, b"AVI ")) {
+        Some(FileKind::Video)
+    } else if at(0, b"%PDF") || at(0, b"{\\rtf") {
+        Some(FileKind::Document)
+    } else if at(0, b"PK\x03\x04")
+        || at(0, b"\x1F\x8B")
+        || at(0, b"BZh")
+        || at(0, b"\xFD7zXZ\0")
+        || at(0, b"7z\xBC\xAF\x27\x1C")
+        || at(0, b"\x28\xB5\x2F\xFD")
+        || at(0, b"Rar!")
+        || at(257, b"ustar")
+    {
+        Some(FileKind::Archive)
+    } else if at(0, b"#!") {
+        Some(FileKind::Source)
+    } else {
+        None
+    }
+}
+
+/// Reads the start of the file at `path` to recognise common formats. Returns `None` if the
+/// format isn't recognised or the file can't be read.
+#[must_use]
+pub fn sniff_kind(path: &str) -> Option<FileKind> {
+    let mut header = Vec::with_capacity(SNIFF_LENGTH);
+    File::open(path)
+        .ok()?
+        .take(SNIFF_LENGTH as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    kind_from_magic(&header)
+}
+
+/// Returns true if the file name of `path` ends with one of `extensions`, ignoring case and any
+/// leading `.`, so multi-part extensions like `tar.gz` work too.
+#[must_use]
+pub fn has_extension(path: &str, extensions: &[String]) -> bool {
+    let Some(file_name) = Path::new(path).file_name() else {
+        return false;
+    };
+    let lower_file_name = file_name.to_string_lossy().to_lowercase();
+
+    extensions.iter().any(|ext| {
+        lower_file_name
+            .strip_suffix(&ext.trim_start_matches('.').to_lowercase())
+            .is_some_and(|stem| stem.len() > 1 && stem.ends_with('.'))
+    })
+}
+
+/// Returns true if the file at `path` is one of `kinds`, judged by its extension or, if `sniff`
+/// is set and the extension doesn't say, by its contents.
+#[must_use]
+pub fn matches_kind(path: &str, kinds: &[FileKind], sniff: bool) -> bool {
+    kind_from_extension(path)
+        .or_else(|| sniff.then(|| sniff_kind(path)).flatten())
+        .is_some_and(|kind| kinds.contains(&kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_extension() {
+        assert_eq!(kind_from_extension("/a/photo.JPG"), Some(FileKind::Image));
+        assert_eq!(kind_from_extension("/a/clip.mkv"), Some(FileKind::Video));
+        assert_eq!(
+            kind_from_extension("/a/README.md"),
+            Some(FileKind::Document)
+        );
+        assert_eq!(kind_from_extension("/a/main.rs"), Some(FileKind::Source));
+        assert_eq!(
+            kind_from_extension("/a/backup.tar.gz"),
+            Some(FileKind::Archive)
+        );
+        assert_eq!(kind_from_extension("/a/Makefile"), None);
+        assert_eq!(kind_from_extension("/a/data.xyz"), None);
+    }
+
+    #[test]
+    fn test_kind_from_magic() {
+        assert_eq!(kind_from_magic(b"\x89PNG\r\n\x1a\n"), Some(FileKind::Image));
+        assert_eq!(
+            kind_from_magic(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(FileKind::Image)
+        );
+        assert_eq!(
+            kind_from_magic(b"\0\0\0\x18ftypisom"),
+            Some(FileKind::Video)
+        );
+        assert_eq!(
+            kind_from_magic(b"\0\0\0\x18ftypheic"),
+            Some(FileKind::Image)
+        );
+        assert_eq!(kind_from_magic(b"%PDF-1.7"), Some(FileKind::Document));
+        assert_eq!(kind_from_magic(b"PK\x03\x04"), Some(FileKind::Archive));
+        assert_eq!(kind_from_magic(b"#!/bin/sh\n"), Some(FileKind::Source));
+        assert_eq!(kind_from_magic(b"hello"), None);
+        assert_eq!(kind_from_magic(b""), None);
+
+        let mut tar = vec![0; 262];
+        tar[257..].copy_from_slice(b"ustar");
+        assert_eq!(kind_from_magic(&tar), Some(FileKind::Archive));
+    }
+
+    #[test]
+    fn test_has_extension() {
+        let extensions = ["md".to_owned(), ".TXT".to_owned(), "tar.gz".to_owned()];
+        assert!(has_extension("/a/notes.md", &extensions));
+        assert!(has_extension("/a/NOTES.txt", &extensions));
+        assert!(has_extension("/a/backup.tar.gz", &extensions));
+        assert!(!has_extension("/a/backup.gz", &extensions));
+        assert!(!has_extension("/a/md", &extensions));
+        assert!(!has_extension("/a/.md", &extensions));
+        assert!(!has_extension("/a/notes.mdx", &extensions));
+    }
+}
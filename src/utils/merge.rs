@@ -0,0 +1,375 @@
+use core::fmt;
+use rusqlite::{Connection, params};
+use std::collections::HashMap;
+use tracing::debug;
+
+use super::cli::MergeStrategy;
+use super::db;
+use super::db::{TableEventsEntry, TablePathsEntry};
+use super::time::{get_iso8601, get_timestamp_now};
+use super::types::UnixTimestamp;
+
+pub enum MergeOutcome {
+    /// The path is not in the local database and will be added as-is.
+    Add(TablePathsEntry),
+    /// The path is already in the local database and will be replaced with the merged entry.
+    Update {
+        existing: TablePathsEntry,
+        merged: TablePathsEntry,
+    },
+    /// Merging makes no difference to the local entry.
+    Unchanged,
+}
+
+#[derive(Default)]
+pub struct MergeSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+impl MergeSummary {
+    #[must_use]
+    pub fn from_outcomes(outcomes: &[MergeOutcome]) -> Self {
+        let mut summary = Self::default();
+        for outcome in outcomes {
+            match outcome {
+                MergeOutcome::Add(_) => summary.added += 1,
+                MergeOutcome::Update { .. } => summary.updated += 1,
+                MergeOutcome::Unchanged => summary.unchanged += 1,
+            }
+        }
+        summary
+    }
+}
+
+impl fmt::Display for MergeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} added, {} updated, {} unchanged",
+            self.added, self.updated, self.unchanged
+        )
+    }
+}
+
+impl fmt::Display for MergeOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add(entry) => write!(
+                f,
+                "add {} (count {}, last noted {})",
+                entry.path,
+                entry.noted_count,
+                get_iso8601(entry.last_noted_timestamp)
+            ),
+            Self::Update { existing, merged } => write!(
+                f,
+                "update {} (count {} -> {}, last noted {} -> {})",
+                merged.path,
+                existing.noted_count,
+                merged.noted_count,
+                get_iso8601(existing.last_noted_timestamp),
+                get_iso8601(merged.last_noted_timestamp)
+            ),
+            Self::Unchanged => write!(f, "unchanged"),
+        }
+    }
+}
+
+#[must_use]
+pub fn merge_entry(
+    existing_entry: Option<&TablePathsEntry>,
+    incoming: &TablePathsEntry,
+    strategy: MergeStrategy,
+) -> MergeOutcome {
+    let Some(existing) = existing_entry else {
+        return MergeOutcome::Add(incoming.clone());
+    };
+
+    let last_noted_timestamp = existing
+        .last_noted_timestamp
+        .max(incoming.last_noted_timestamp);
+    // A pin is a deliberate choice on either side, so it's never lost by merging.
+    let pinned = existing.pinned || incoming.pinned;
+
+    let merged = match strategy {
+        MergeStrategy::Sum => TablePathsEntry {
+            noted_count: existing.noted_count.saturating_add(incoming.noted_count),
+            last_noted_timestamp,
+            pinned,
+            ..existing.clone()
+        },
+        MergeStrategy::Max => TablePathsEntry {
+            noted_count: existing.noted_count.max(incoming.noted_count),
+            last_noted_timestamp,
+            pinned,
+            ..existing.clone()
+        },
+        MergeStrategy::Newest => {
+            let newest = if incoming.last_noted_timestamp > existing.last_noted_timestamp {
+                incoming
+            } else {
+                existing
+            };
+            TablePathsEntry {
+                pinned,
+                ..newest.clone()
+            }
+        }
+    };
+
+    if merged == *existing {
+        MergeOutcome::Unchanged
+    } else {
+        MergeOutcome::Update {
+            existing: existing.clone(),
+            merged,
+        }
+    }
+}
+
+/// Merges rows of `incoming` for the same path with each other using `strategy`, keeping the
+/// first row's place, as an export file may list a path more than once.
+fn combine_duplicates(
+    incoming: &[TablePathsEntry],
+    strategy: MergeStrategy,
+) -> Vec<TablePathsEntry> {
+    let mut combined: Vec<TablePathsEntry> = Vec::with_capacity(incoming.len());
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+
+    for entry in incoming {
+        if let Some(&position) = positions.get(entry.path.as_str()) {
+            if let Some(earlier) = combined.get_mut(position)
+                && let MergeOutcome::Update { merged, .. } =
+                    merge_entry(Some(earlier), entry, strategy)
+            {
+                *earlier = merged;
+            }
+        } else {
+            positions.insert(&entry.path, combined.len());
+            combined.push(entry.clone());
+        }
+    }
+
+    combined
+}
+
+/// Works out how each of `incoming` would be merged into the database behind `conn`, without
+/// changing anything.
+pub fn plan(
+    conn: &Connection,
+    incoming: &[TablePathsEntry],
+    strategy: MergeStrategy,
+) -> Result<Vec<MergeOutcome>, rusqlite::Error> {
+    let existing: HashMap<String, TablePathsEntry> = db::get_rows(conn)?
+        .into_iter()
+        .map(|row| (row.path.clone(), row))
+        .collect();
+
+    Ok(combine_duplicates(incoming, strategy)
+        .iter()
+        .map(|entry| merge_entry(existing.get(&entry.path), entry, strategy))
+        .collect())
+}
+
+/// Copies `events` for `path`, except those already recorded locally, so merging the same
+/// database twice doesn't record its notes twice.
+fn copy_events(
+    conn: &Connection,
+    path: &str,
+    events: &[TableEventsEntry],
+) -> Result<(), rusqlite::Error> {
+    let mut local: HashMap<(UnixTimestamp, Option<String>, Option<String>), usize> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT timestamp, source, cwd FROM events WHERE path = ?1")?;
+    for key in stmt.query_map([path], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))? {
+        *local.entry(key?).or_default() += 1;
+    }
+
+    for event in events {
+        let key = (event.timestamp, event.source.clone(), event.cwd.clone());
+        match local.get_mut(&key) {
+            Some(remaining) if *remaining > 0 => *remaining -= 1,
+            _ => db::insert_event(
+                conn,
+                path,
+                event.timestamp,
+                event.source.as_deref(),
+                event.cwd.as_deref(),
+            )?,
+        }
+    }
+    Ok(())
+}
+
+/// Applies outcomes planned with `strategy` to the database. The incoming notes for each path are
+/// taken from `incoming_events` where they're known, added to the local ones when summing or
+/// replacing them when the incoming count was chosen. Otherwise only the most recent note is
+/// known, so an event is recorded for it only when it's newer than the local entry's last note.
+pub fn apply(
+    conn: &Connection,
+    outcomes: &[MergeOutcome],
+    incoming_events: &HashMap<String, Vec<TableEventsEntry>>,
+    strategy: MergeStrategy,
+) -> Result<(), rusqlite::Error> {
+    let mut has_synthetic_history = false;
+
+    for outcome in outcomes {
+        let (entry, is_new_note) = match outcome {
+            MergeOutcome::Add(entry) => (entry, true),
+            MergeOutcome::Update { existing, merged } => (
+                merged,
+                merged.last_noted_timestamp > existing.last_noted_timestamp,
+            ),
+            MergeOutcome::Unchanged => continue,
+        };
+
+        conn.execute(
+            "INSERT INTO paths (path, noted_count, last_noted_timestamp, pinned)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(path) DO UPDATE SET
+             noted_count = excluded.noted_count,
+             last_noted_timestamp = excluded.last_noted_timestamp,
+             pinned = excluded.pinned",
+            params![
+                entry.path,
+                entry.noted_count,
+                entry.last_noted_timestamp,
+                entry.pinned
+            ],
+        )?;
+        if let Some(events) = incoming_events.get(&entry.path) {
+            match outcome {
+                // Max and Newest take one side's count, so the notes are that side's too
+                MergeOutcome::Update { existing, merged } if strategy != MergeStrategy::Sum => {
+                    if merged.noted_count != existing.noted_count {
+                        conn.execute("DELETE FROM events WHERE path = ?1", [&entry.path])?;
+                        copy_events(conn, &entry.path, events)?;
+                    }
+                }
+                _ => copy_events(conn, &entry.path, events)?,
+            }
+        } else {
+            has_synthetic_history = true;
+            if is_new_note {
+                db::insert_event(conn, &entry.path, entry.last_noted_timestamp, None, None)?;
+            }
+        }
+        debug!("Merged entry for {}", entry.path);
+    }
+
+    // Noted counts now include notes that have no event
+    if has_synthetic_history {
+        db::set_events_complete_since(conn, get_timestamp_now())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(noted_count: u64, last_noted_timestamp: i64) -> TablePathsEntry {
+        TablePathsEntry {
+            path: "/a".to_owned(),
+            noted_count,
+            last_noted_timestamp,
+            pinned: false,
+        }
+    }
+
+    fn merged(outcome: MergeOutcome) -> Option<TablePathsEntry> {
+        match outcome {
+            MergeOutcome::Add(entry) | MergeOutcome::Update { merged: entry, .. } => Some(entry),
+            MergeOutcome::Unchanged => None,
+        }
+    }
+
+    #[test]
+    fn test_merge_new_path_is_added() {
+        let outcome = merge_entry(None, &entry(3, 100), MergeStrategy::Sum);
+        assert!(matches!(outcome, MergeOutcome::Add(_)));
+    }
+
+    #[test]
+    fn test_merge_sum() {
+        let result = merged(merge_entry(
+            Some(&entry(3, 200)),
+            &entry(2, 100),
+            MergeStrategy::Sum,
+        ));
+        assert_eq!(result, Some(entry(5, 200)));
+    }
+
+    #[test]
+    fn test_merge_max() {
+        let result = merged(merge_entry(
+            Some(&entry(3, 100)),
+            &entry(2, 200),
+            MergeStrategy::Max,
+        ));
+        assert_eq!(result, Some(entry(3, 200)));
+    }
+
+    #[test]
+    fn test_merge_newest() {
+        let result = merged(merge_entry(
+            Some(&entry(3, 100)),
+            &entry(2, 200),
+            MergeStrategy::Newest,
+        ));
+        assert_eq!(result, Some(entry(2, 200)));
+    }
+
+    #[test]
+    fn test_merge_unchanged() {
+        let outcome = merge_entry(Some(&entry(3, 200)), &entry(2, 100), MergeStrategy::Newest);
+        assert!(matches!(outcome, MergeOutcome::Unchanged));
+
+        let outcome_max = merge_entry(Some(&entry(3, 200)), &entry(3, 200), MergeStrategy::Max);
+        assert!(matches!(outcome_max, MergeOutcome::Unchanged));
+    }
+
+    #[test]
+    fn test_combine_duplicates() {
+        let other = TablePathsEntry {
+            path: "/b".to_owned(),
+            ..entry(1, 100)
+        };
+        let incoming = [entry(3, 100), other.clone(), entry(2, 200)];
+
+        assert_eq!(
+            combine_duplicates(&incoming, MergeStrategy::Sum),
+            [entry(5, 200), other.clone()]
+        );
+        assert_eq!(
+            combine_duplicates(&incoming, MergeStrategy::Newest),
+            [entry(2, 200), other]
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_pin_from_either_side() {
+        let pinned_entry = TablePathsEntry {
+            pinned: true,
+            ..entry(3, 100)
+        };
+
+        let from_incoming = merged(merge_entry(
+            Some(&entry(3, 200)),
+            &pinned_entry,
+            MergeStrategy::Newest,
+        ));
+        assert_eq!(
+            from_incoming,
+            Some(TablePathsEntry {
+                pinned: true,
+                ..entry(3, 200)
+            })
+        );
+
+        let from_existing = merge_entry(Some(&pinned_entry), &entry(1, 50), MergeStrategy::Max);
+        assert!(matches!(from_existing, MergeOutcome::Unchanged));
+    }
+}
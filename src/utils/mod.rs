@@ -4,11 +4,15 @@ pub mod db;
 pub mod denylist_default;
 pub mod frecency;
 pub mod graphs;
+pub mod kind;
 pub mod logging;
+pub mod merge;
 pub mod output;
 pub mod path;
+pub mod picker;
 pub mod query;
 pub mod search;
+pub mod template;
 pub mod time;
 pub mod types;
 
@@ -30,10 +34,15 @@ pub fn serialize_file_type<S>(ft: &std::fs::FileType, s: S) -> Result<S::Ok, S::
 where
     S: serde::Serializer,
 {
-    s.serialize_str(match (ft.is_dir(), ft.is_file(), ft.is_symlink()) {
+    s.serialize_str(file_type_name(*ft))
+}
+
+#[must_use]
+pub fn file_type_name(ft: std::fs::FileType) -> &'static str {
+    match (ft.is_dir(), ft.is_file(), ft.is_symlink()) {
         (true, _, _) => "dir",
         (_, true, _) => "file",
         (_, _, true) => "symlink",
         _ => "other",
-    })
+    }
 }
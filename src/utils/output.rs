@@ -11,13 +11,21 @@ use super::config;
 use super::is_command_available;
 use super::path::expand_tildes_in_multiline_string;
 
-fn format_path_colored(path: &str, is_dir: bool, use_pretty_paths: bool) -> String {
-    let display: Cow<str> = if use_pretty_paths {
+const PINNED_MARKER: &str = "(pinned)";
+
+const LONG_HEADERS: [&str; 4] = ["FRECENCY", "COUNT", "LAST NOTED", "TYPE"];
+const LONG_PATH_HEADER: &str = "PATH";
+const MIN_LONG_PATH_WIDTH: usize = 20;
+
+fn display_path(path: &str, use_pretty_paths: bool) -> Cow<'_, str> {
+    if use_pretty_paths {
         Cow::Owned(path::collapse_to_tilde(path))
     } else {
         Cow::Borrowed(path)
-    };
+    }
+}
 
+fn color_path(display: &str, is_dir: bool) -> String {
     if let Some((parent, base)) = display.rsplit_once('/') {
         if is_dir {
             format!("{}/{}", parent, base.blue())
@@ -31,64 +39,167 @@ fn format_path_colored(path: &str, is_dir: bool, use_pretty_paths: bool) -> Stri
     }
 }
 
+/// Shortens `path` to at most `width` characters by replacing its start with `…`, so the most
+/// specific components stay visible.
+#[must_use]
+pub fn truncate_path_left(path: &str, width: usize) -> Cow<'_, str> {
+    let len = path.chars().count();
+    if len <= width {
+        return Cow::Borrowed(path);
+    }
+
+    let kept: String = path.chars().skip(len - width + 1).collect();
+    Cow::Owned(format!("…{kept}"))
+}
+
+/// Formats `(path, is_dir, show_pinned_marker)` items one per line.
 pub fn format_paths_colored<'a>(
-    items: impl Iterator<Item = (&'a str, bool)>,
+    items: impl Iterator<Item = (&'a str, bool, bool)>,
     use_pretty_paths: bool,
 ) -> String {
     let mut output = String::new();
-    for (path, is_dir) in items {
-        output.push_str(&format_path_colored(path, is_dir, use_pretty_paths));
+    for (path, is_dir, show_pinned_marker) in items {
+        output.push_str(&color_path(&display_path(path, use_pretty_paths), is_dir));
+        if show_pinned_marker {
+            output.push(' ');
+            output.push_str(&PINNED_MARKER.dimmed().to_string());
+        }
         output.push('\n');
     }
     output
 }
 
-fn get_output_filter_command(override_cmd: Option<&str>) -> Result<String, &str> {
+/// One line of a long listing.
+pub struct LongRow<'a> {
+    pub path: &'a str,
+    pub is_dir: bool,
+    pub show_pinned_marker: bool,
+    /// Frecency, noted count, last noted age and file type.
+    pub columns: [String; 4],
+}
+
+/// Formats `rows` as aligned columns under a header, with the path last. With `max_width`, paths
+/// are shortened from the left so that each line fits, if that leaves them a reasonable width.
+pub fn format_long_listing(
+    rows: &[LongRow<'_>],
+    use_pretty_paths: bool,
+    max_width: Option<usize>,
+) -> String {
+    let mut widths = LONG_HEADERS.map(str::len);
+    for row in rows {
+        for (width, column) in widths.iter_mut().zip(&row.columns) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+    let [frecency_width, count_width, age_width, type_width] = widths;
+    let prefix_width: usize = widths.iter().map(|width| width + 2).sum();
+
+    let line_prefix = |columns: &[&str; 4]| {
+        format!(
+            "{:>frecency_width$}  {:>count_width$}  {:>age_width$}  {:<type_width$}  ",
+            columns[0], columns[1], columns[2], columns[3]
+        )
+    };
+
+    let mut output = line_prefix(&LONG_HEADERS);
+    output.push_str(LONG_PATH_HEADER);
+    output = output.dimmed().to_string();
+    output.push('\n');
+
+    for row in rows {
+        output.push_str(&line_prefix(&row.columns.each_ref().map(String::as_str)));
+
+        let display = display_path(row.path, use_pretty_paths);
+        let marker_width = if row.show_pinned_marker {
+            PINNED_MARKER.len() + 1
+        } else {
+            0
+        };
+        let shortened = match max_width {
+            Some(width) => truncate_path_left(
+                &display,
+                width
+                    .saturating_sub(prefix_width + marker_width)
+                    .max(MIN_LONG_PATH_WIDTH),
+            )
+            .into_owned(),
+            None => display.into_owned(),
+        };
+        output.push_str(&color_path(&shortened, row.is_dir));
+
+        if row.show_pinned_marker {
+            output.push(' ');
+            output.push_str(&PINNED_MARKER.dimmed().to_string());
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Where `--output-filter` sends the list.
+pub enum OutputFilter {
+    /// A shell command that reads the list and prints the selected lines, like `fzf`.
+    Command(String),
+    /// memy's own picker (see [`super::picker`]).
+    Builtin,
+}
+
+/// The output filter setting that chooses the built-in picker even when an external filter is
+/// installed.
+const BUILTIN_OUTPUT_FILTER: &str = "builtin";
+
+fn output_filter_from(cmd: String) -> OutputFilter {
+    if cmd == BUILTIN_OUTPUT_FILTER {
+        OutputFilter::Builtin
+    } else {
+        OutputFilter::Command(cmd)
+    }
+}
+
+/// Chooses the output filter, falling back to the built-in picker if no command is configured
+/// and none of the usual ones are installed.
+#[must_use]
+pub fn get_output_filter(override_cmd: Option<&str>) -> OutputFilter {
     if let Some(cmd) = override_cmd {
         debug!("Output filter detected from command line: {cmd}");
-        return Ok(cmd.to_owned());
+        return output_filter_from(cmd.to_owned());
     }
 
     if let Ok(cmd) = env::var("MEMY_OUTPUT_FILTER")
         && !cmd.is_empty()
     {
         debug!("Output filter detected from environment: {cmd}");
-        return Ok(cmd);
+        return output_filter_from(cmd);
     }
 
     if let Some(cmd) = config::get_memy_output_filter() {
         debug!("Output filter detected from config: {cmd}");
-        return Ok(cmd);
+        return output_filter_from(cmd);
     }
 
     if is_command_available("fzf") {
         debug!("Output filter automatically set for fzf");
-        return Ok("fzf --ansi".to_owned());
+        return OutputFilter::Command("fzf --ansi".to_owned());
     }
 
     if is_command_available("sk") {
         debug!("Output filter automatically set for sk");
-        return Ok("sk --ansi".to_owned());
+        return OutputFilter::Command("sk --ansi".to_owned());
     }
 
     if is_command_available("fzy") {
         debug!("Output filter automatically set for fzy");
-        return Ok("fzy".to_owned());
+        return OutputFilter::Command("fzy".to_owned());
     }
 
-    Err(
-        "No output filter command found. Set MEMY_OUTPUT_FILTER environment variable, \
-         memy_output_filter in config, or install fzf/sk/fzy.",
-    )
+    debug!("No output filter command found, using the built-in picker");
+    OutputFilter::Builtin
 }
 
 pub fn pipe_through_filter(
     input: &str,
-    override_cmd: Option<&str>,
+    filter_cmd: &str,
 ) -> Result<String, Box<dyn core::error::Error>> {
-    let filter_cmd_string = get_output_filter_command(override_cmd)?;
-    let filter_cmd = filter_cmd_string.as_str();
-
     debug!("Running through external filter command {filter_cmd}");
 
     let shell = env::var("SHELL")
@@ -127,3 +238,57 @@ pub fn pipe_through_filter(
         .map_err(|_| "Output filter output is not valid UTF-8")?;
     Ok(expand_tildes_in_multiline_string(&result))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_path_left() {
+        assert_eq!(truncate_path_left("/a/b/c.txt", 10), "/a/b/c.txt");
+        assert_eq!(truncate_path_left("/a/b/c.txt", 8), "…b/c.txt");
+        assert_eq!(truncate_path_left("/ä/ö/ü.txt", 8), "…ö/ü.txt");
+    }
+
+    #[test]
+    fn test_format_long_listing_aligns_columns() {
+        colored::control::set_override(false);
+        let rows = [
+            LongRow {
+                path: "/tmp/a",
+                is_dir: true,
+                show_pinned_marker: false,
+                columns: [
+                    "12.50".to_owned(),
+                    "3".to_owned(),
+                    "2d ago".to_owned(),
+                    "dir".to_owned(),
+                ],
+            },
+            LongRow {
+                path: "/tmp/a/some/longer/path/b.txt",
+                is_dir: false,
+                show_pinned_marker: true,
+                columns: [
+                    "0.25".to_owned(),
+                    "120".to_owned(),
+                    "just now".to_owned(),
+                    "file".to_owned(),
+                ],
+            },
+        ];
+
+        assert_eq!(
+            format_long_listing(&rows, false, None),
+            "FRECENCY  COUNT  LAST NOTED  TYPE  PATH\n\
+             \x20  12.50      3      2d ago  dir   /tmp/a\n\
+             \x20   0.25    120    just now  file  /tmp/a/some/longer/path/b.txt (pinned)\n"
+        );
+
+        let narrow = format_long_listing(&rows, false, Some(70));
+        assert!(
+            narrow.ends_with("  …/a/some/longer/path/b.txt (pinned)\n"),
+            "{narrow}"
+        );
+    }
+}
@@ -26,6 +26,26 @@ pub fn resolve_existing_dir(arg: &str) -> Option<PathBuf> {
     }
 }
 
+/// Returns the forms a path given on the command line may have been stored under: the absolute
+/// path as given, and the canonical path if it still exists (symlinks are normalized on note by
+/// default).
+#[must_use]
+pub fn candidate_stored_paths(raw_path: &str) -> Vec<String> {
+    let expanded = expand_tilde_in_path(raw_path);
+    let absolute: PathBuf = match std::env::current_dir() {
+        Ok(cwd) if !expanded.is_absolute() => cwd.join(&expanded),
+        _ => expanded.into_owned(),
+    };
+
+    let mut candidates = vec![normalize_path(&absolute).to_string_lossy().into_owned()];
+
+    if let Ok(canonical) = absolute.canonicalize() {
+        candidates.push(canonical.to_string_lossy().into_owned());
+    }
+
+    candidates
+}
+
 #[must_use]
 pub fn expand_tilde_in_path<P: AsRef<Path> + ?Sized>(path: &'_ P) -> Cow<'_, Path> {
     let p = path.as_ref();
@@ -91,6 +111,16 @@ pub fn collapse_to_tilde<P: AsRef<Path>>(path: P) -> String {
     p.to_string_lossy().into_owned()
 }
 
+/// Returns the root of the git work tree containing `start`: the nearest ancestor with a `.git`
+/// directory, or a `.git` file for linked worktrees and submodules.
+#[must_use]
+pub fn find_git_work_tree(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
 #[allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
 #[cfg(test)]
 mod tests {
@@ -150,6 +180,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_find_git_work_tree() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        let nested = repo.join("src").join("utils");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir(repo.join(".git")).unwrap();
+
+        assert_eq!(find_git_work_tree(&nested), Some(repo.clone()));
+        assert_eq!(find_git_work_tree(&repo), Some(repo));
+    }
+
+    #[test]
+    fn test_find_git_work_tree_worktree_file() {
+        let tmp = TempDir::new().unwrap();
+        let worktree = tmp.path().join("worktree");
+        std::fs::create_dir(&worktree).unwrap();
+        std::fs::write(worktree.join(".git"), "gitdir: /elsewhere").unwrap();
+
+        assert_eq!(find_git_work_tree(&worktree), Some(worktree));
+    }
+
     fn generate_unix_path() -> impl Strategy<Value = String> {
         let component_char = r"[^/]+";
         let components = proptest::collection::vec(
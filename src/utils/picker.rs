@@ -0,0 +1,556 @@
+use core::error::Error;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Print, Stylize as _};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{BufWriter, IsTerminal as _, Write, stderr};
+use std::path::Path;
+
+use super::cli::MatchMode;
+use super::output::truncate_path_left;
+use super::search;
+use super::time;
+
+const HELP: &str = "Tab select · Enter accept · Ctrl-X forget · Esc cancel";
+const MIN_PREVIEW_WIDTH: usize = 80;
+
+/// A path offered by the picker.
+pub struct PickerItem {
+    /// What's shown in the list, and printed if it's picked.
+    pub display: String,
+    /// The path as stored in the database, which keywords are matched against.
+    pub path: String,
+    pub is_dir: bool,
+    /// Labelled values shown at the top of the preview pane, like the noted count.
+    pub details: Vec<(&'static str, String)>,
+}
+
+enum Action {
+    Continue,
+    Accept,
+    Cancel,
+    /// Forget the item at this index into the items.
+    Forget(usize),
+}
+
+struct Picker {
+    items: Vec<PickerItem>,
+    match_mode: MatchMode,
+    query: String,
+    /// Indexes into `items` of the items matching `query`, in order.
+    visible: Vec<usize>,
+    /// Index into `visible` of the highlighted item.
+    cursor: usize,
+    /// Index into `visible` of the first item shown.
+    scroll: usize,
+    /// Indexes into `items` of the items selected with Tab.
+    selected: BTreeSet<usize>,
+    message: Option<String>,
+}
+
+impl Picker {
+    fn new(items: Vec<PickerItem>, match_mode: MatchMode) -> Self {
+        let mut picker = Self {
+            items,
+            match_mode,
+            query: String::new(),
+            visible: vec![],
+            cursor: 0,
+            scroll: 0,
+            selected: BTreeSet::new(),
+            message: None,
+        };
+        picker.refilter();
+        picker
+    }
+
+    fn refilter(&mut self) {
+        let keywords: Vec<String> = self.query.split_whitespace().map(str::to_owned).collect();
+
+        self.visible = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| match self.match_mode {
+                MatchMode::Zoxide => search::matches_zoxide_algo(&item.path, &keywords),
+                MatchMode::Fuzzy => search::fuzzy_match_score(&item.path, &keywords).is_some(),
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.cursor = self.cursor.min(self.visible.len().saturating_sub(1));
+    }
+
+    fn current(&self) -> Option<usize> {
+        self.visible.get(self.cursor).copied()
+    }
+
+    fn move_cursor(&mut self, down: bool) {
+        if down {
+            self.cursor = (self.cursor + 1).min(self.visible.len().saturating_sub(1));
+        } else {
+            self.cursor = self.cursor.saturating_sub(1);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        self.message = None;
+
+        match key.code {
+            KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('c' | 'g') if ctrl => return Action::Cancel,
+            KeyCode::Enter => return Action::Accept,
+            KeyCode::Char('x') if ctrl => {
+                if let Some(index) = self.current() {
+                    return Action::Forget(index);
+                }
+            }
+            KeyCode::Up => self.move_cursor(false),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_cursor(false),
+            KeyCode::Down => self.move_cursor(true),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_cursor(true),
+            KeyCode::Tab => {
+                if let Some(index) = self.current() {
+                    if !self.selected.remove(&index) {
+                        self.selected.insert(index);
+                    }
+                    self.move_cursor(true);
+                }
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refilter();
+            }
+            _ => {}
+        }
+
+        Action::Continue
+    }
+
+    /// Removes the item at `index` after it's been forgotten.
+    fn remove(&mut self, index: usize) {
+        self.items.remove(index);
+        self.selected = self
+            .selected
+            .iter()
+            .filter(|&&selected| selected != index)
+            .map(|&selected| {
+                if selected > index {
+                    selected - 1
+                } else {
+                    selected
+                }
+            })
+            .collect();
+        self.refilter();
+    }
+
+    /// The selected items, or the highlighted one if none are.
+    fn picked(&self) -> Vec<&PickerItem> {
+        if self.selected.is_empty() {
+            self.current()
+                .map(|index| vec![&self.items[index]])
+                .unwrap_or_default()
+        } else {
+            self.selected
+                .iter()
+                .map(|&index| &self.items[index])
+                .collect()
+        }
+    }
+
+    fn list_line(&self, index: usize, width: usize) -> String {
+        let item = &self.items[index];
+        let is_current = self.current() == Some(index);
+        let marker = if self.selected.contains(&index) {
+            '*'
+        } else {
+            ' '
+        };
+        let pointer = if is_current { '>' } else { ' ' };
+
+        let display = truncate_path_left(&item.display, width.saturating_sub(3));
+        let text = format!("{pointer}{marker} {display}");
+
+        if is_current {
+            let padding = width.saturating_sub(text.chars().count());
+            format!("{text}{}", " ".repeat(padding))
+                .reverse()
+                .to_string()
+        } else if item.is_dir {
+            format!("{pointer}{marker} {}", display.blue())
+        } else {
+            format!("{pointer}{marker} {}", display.green())
+        }
+    }
+
+    fn render(&mut self, out: &mut impl Write, width: usize, height: usize) -> std::io::Result<()> {
+        let list_height = height.saturating_sub(2);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if list_height > 0 && self.cursor >= self.scroll + list_height {
+            self.scroll = self.cursor + 1 - list_height;
+        }
+
+        let (list_width, preview_width) = if width >= MIN_PREVIEW_WIDTH {
+            (width / 2, width - width / 2 - 3)
+        } else {
+            (width, 0)
+        };
+        let preview = match self.current() {
+            Some(index) if preview_width > 0 => preview_lines(&self.items[index], list_height),
+            _ => vec![],
+        };
+
+        let status = self.message.clone().unwrap_or_else(|| {
+            let selected = if self.selected.is_empty() {
+                String::new()
+            } else {
+                format!(" ({} selected)", self.selected.len())
+            };
+            format!(
+                "  {}/{}{selected}  {HELP}",
+                self.visible.len(),
+                self.items.len()
+            )
+        });
+
+        queue!(
+            out,
+            cursor::MoveTo(0, 1),
+            Print(truncate_end(&status, width).dim()),
+            terminal::Clear(ClearType::UntilNewLine)
+        )?;
+
+        for row in 0..list_height {
+            let line = self
+                .visible
+                .get(self.scroll + row)
+                .map(|&index| self.list_line(index, list_width))
+                .unwrap_or_default();
+            queue!(
+                out,
+                cursor::MoveTo(0, u16::try_from(row + 2).unwrap_or(u16::MAX)),
+                Print(line),
+                terminal::Clear(ClearType::UntilNewLine)
+            )?;
+
+            if preview_width > 0 {
+                queue!(
+                    out,
+                    cursor::MoveTo(
+                        u16::try_from(list_width).unwrap_or(u16::MAX),
+                        u16::try_from(row + 2).unwrap_or(u16::MAX)
+                    ),
+                    Print(" │ ".dim()),
+                    Print(truncate_end(
+                        preview.get(row).map_or("", String::as_str),
+                        preview_width
+                    )),
+                    terminal::Clear(ClearType::UntilNewLine)
+                )?;
+            }
+        }
+
+        let prompt = format!("> {}", self.query);
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            Print(&prompt),
+            terminal::Clear(ClearType::UntilNewLine),
+            cursor::MoveTo(u16::try_from(prompt.chars().count()).unwrap_or(u16::MAX), 0)
+        )?;
+        out.flush()
+    }
+}
+
+fn truncate_end(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "Only shown to one decimal place"
+    )]
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// The preview of `item`: its details, then its size and modification time if it's a file, or
+/// what's in it if it's a directory.
+fn preview_lines(item: &PickerItem, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = item
+        .details
+        .iter()
+        .map(|(label, value)| format!("{label}: {value}"))
+        .collect();
+
+    if item.is_dir {
+        let mut entries: Vec<String> = fs::read_dir(&item.path)
+            .map(|read_dir| {
+                read_dir
+                    .filter_map(Result::ok)
+                    .map(|entry| {
+                        let name = entry.file_name().to_string_lossy().into_owned();
+                        if entry.file_type().is_ok_and(|ft| ft.is_dir()) {
+                            format!("{name}/")
+                        } else {
+                            name
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        entries.sort_by_key(|name| (!name.ends_with('/'), name.to_lowercase()));
+
+        lines.push(String::new());
+        let room = max_lines.saturating_sub(lines.len());
+        if entries.len() > room {
+            let shown = room.saturating_sub(1);
+            let hidden = entries.len() - shown;
+            entries.truncate(shown);
+            entries.push(format!("… and {hidden} more"));
+        }
+        lines.extend(entries);
+    } else if let Ok(metadata) = fs::metadata(Path::new(&item.path)) {
+        lines.push(format!("Size: {}", format_size(metadata.len())));
+        if let Ok(modified) = metadata.modified()
+            && let Ok(since_epoch) = modified.duration_since(std::time::UNIX_EPOCH)
+        {
+            lines.push(format!(
+                "Modified: {}",
+                time::format_relative(
+                    time::get_timestamp_now(),
+                    since_epoch.as_secs().cast_signed()
+                )
+            ));
+        }
+        if metadata.permissions().readonly() {
+            lines.push("Read-only".to_owned());
+        }
+    }
+
+    lines
+}
+
+/// Restores the terminal when dropped, even on errors.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stderr(), terminal::EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Nothing more can be done if these fail
+        let _: std::io::Result<()> = execute!(stderr(), terminal::LeaveAlternateScreen);
+        let _: std::io::Result<()> = terminal::disable_raw_mode();
+    }
+}
+
+/// Returns true if there's a terminal for the picker to run in. It draws on stderr, so that
+/// stdout can be captured by the shell.
+#[must_use]
+pub fn is_available() -> bool {
+    stderr().is_terminal()
+}
+
+/// Lets the user pick from `items` interactively, filtering them by keywords typed using
+/// `match_mode`. `forget` is called with the stored path of an item to forget it. Returns the
+/// displayed form of the picked items, which is empty if the user cancelled.
+pub fn pick(
+    items: Vec<PickerItem>,
+    match_mode: MatchMode,
+    mut forget: impl FnMut(&str) -> Result<(), Box<dyn Error>>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut picker = Picker::new(items, match_mode);
+    let _guard = TerminalGuard::new()?;
+    let mut out = BufWriter::new(stderr());
+
+    loop {
+        let (width, height) = terminal::size()?;
+        picker.render(&mut out, usize::from(width), usize::from(height))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match picker.handle_key(key) {
+            Action::Continue => {}
+            Action::Cancel => return Ok(vec![]),
+            Action::Accept => {
+                return Ok(picker
+                    .picked()
+                    .into_iter()
+                    .map(|item| item.display.clone())
+                    .collect());
+            }
+            Action::Forget(index) => match forget(&picker.items[index].path) {
+                Ok(()) => {
+                    picker.message = Some(format!("  Forgot {}", picker.items[index].display));
+                    picker.remove(index);
+                }
+                Err(err) => picker.message = Some(format!("  Couldn't forget: {err}")),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str) -> PickerItem {
+        PickerItem {
+            display: path.to_owned(),
+            path: path.to_owned(),
+            is_dir: false,
+            details: vec![],
+        }
+    }
+
+    fn picker() -> Picker {
+        Picker::new(
+            vec![
+                item("/home/user/notes.md"),
+                item("/home/user/src/memy/Cargo.toml"),
+                item("/home/user/src/other/README.md"),
+            ],
+            MatchMode::Zoxide,
+        )
+    }
+
+    fn press(picker: &mut Picker, code: KeyCode) -> Action {
+        picker.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_query(picker: &mut Picker, query: &str) {
+        for c in query.chars() {
+            press(picker, KeyCode::Char(c));
+        }
+    }
+
+    fn picked_paths(picker: &Picker) -> Vec<&str> {
+        picker
+            .picked()
+            .into_iter()
+            .map(|picked| picked.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_picker_filters_incrementally() {
+        let mut picker = picker();
+        assert_eq!(picker.visible, vec![0, 1, 2]);
+
+        type_query(&mut picker, "md");
+        assert_eq!(picker.visible, vec![0, 2]);
+
+        picker.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        type_query(&mut picker, "src md");
+        assert_eq!(picker.visible, vec![2]);
+
+        press(&mut picker, KeyCode::Backspace);
+        assert_eq!(picker.visible, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_picker_fuzzy_matching() {
+        let mut picker = Picker::new(picker().items, MatchMode::Fuzzy);
+        type_query(&mut picker, "mmycrg");
+        assert_eq!(picker.visible, vec![1]);
+    }
+
+    #[test]
+    fn test_picker_accepts_current_without_selection() {
+        let mut picker = picker();
+        press(&mut picker, KeyCode::Down);
+        assert!(matches!(press(&mut picker, KeyCode::Enter), Action::Accept));
+        assert_eq!(picked_paths(&picker), ["/home/user/src/memy/Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_picker_multi_select() {
+        let mut picker = picker();
+        press(&mut picker, KeyCode::Tab);
+        press(&mut picker, KeyCode::Down);
+        press(&mut picker, KeyCode::Tab);
+        assert_eq!(
+            picked_paths(&picker),
+            ["/home/user/notes.md", "/home/user/src/other/README.md"]
+        );
+
+        // Tab again deselects, and the cursor stays on the last item
+        press(&mut picker, KeyCode::Tab);
+        assert_eq!(picked_paths(&picker), ["/home/user/notes.md"]);
+    }
+
+    #[test]
+    fn test_picker_forget_removes_item() {
+        let mut picker = picker();
+        press(&mut picker, KeyCode::Tab);
+        press(&mut picker, KeyCode::Tab);
+
+        press(&mut picker, KeyCode::Up);
+        let Action::Forget(index) =
+            picker.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL))
+        else {
+            panic!("Ctrl-X should forget");
+        };
+        assert_eq!(index, 1);
+
+        picker.remove(index);
+        assert_eq!(picker.items.len(), 2);
+        assert_eq!(picked_paths(&picker), ["/home/user/notes.md"]);
+        assert_eq!(picker.visible, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_picker_cancel() {
+        let mut picker = picker();
+        assert!(matches!(press(&mut picker, KeyCode::Esc), Action::Cancel));
+        assert!(matches!(
+            picker.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Cancel
+        ));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+}
@@ -1,7 +1,9 @@
 use core::error::Error;
+use ignore::gitignore::Gitignore;
 use rayon::prelude::*;
-use rusqlite::{Connection, params_from_iter};
+use rusqlite::Connection;
 use std::fs::{Metadata, metadata};
+use std::path::Path;
 use tracing::instrument;
 use tracing::{info, warn};
 
@@ -27,16 +29,63 @@ pub enum FilterResult {
 }
 
 enum Outcome {
-    Match(MatchEntry),
+    Match(Box<MatchEntry>),
     Delete(String),
     Skip,
 }
 
-/// Builds a sorted list of frecency matches from the open database connection.
+/// Adds the frecency each file passes to the directories above it (see
+/// [`frecency::derive_dir_frecencies`]) to `matches`, creating entries with a count of 0 for
+/// directories that were never noted.
+fn add_derived_dirs(matches: &mut Vec<MatchEntry>, decay: f64, denylist_matcher: &Gitignore) {
+    let home = std::env::home_dir();
+    let mut derived = frecency::derive_dir_frecencies(
+        matches.iter().filter(|m| m.metadata.is_file()).map(|m| {
+            (
+                Path::new(&m.table_paths_entry.path),
+                m.frecency,
+                m.table_paths_entry.last_noted_timestamp,
+            )
+        }),
+        decay,
+        home.as_deref(),
+    );
+
+    for m in matches.iter_mut() {
+        if let Some(extra) = derived.remove(Path::new(&m.table_paths_entry.path)) {
+            m.frecency += extra.frecency;
+        }
+    }
+
+    matches.extend(derived.into_iter().filter_map(|(dir, extra)| {
+        let meta = metadata(&dir).ok().filter(Metadata::is_dir)?;
+        if denylist_matcher
+            .matched_path_or_any_parents(&dir, true)
+            .is_ignore()
+        {
+            return None;
+        }
+
+        Some(MatchEntry {
+            table_paths_entry: TablePathsEntry {
+                path: dir.to_string_lossy().into_owned(),
+                noted_count: 0,
+                last_noted_timestamp: extra.last_noted_timestamp,
+                pinned: false,
+            },
+            metadata: meta,
+            frecency: extra.frecency,
+        })
+    }));
+}
+
+/// Builds a sorted list of frecency matches from the open database connection, with frecency
+/// derived from files added to their directories if `derive_dirs_decay` is given.
 /// The `filter` closure receives `(&PathEntry, &Metadata)` and returns a [`FilterResult`].
 #[instrument(level = "trace", skip(filter))]
 pub fn build_sorted_matches<F>(
     conn: &Connection,
+    derive_dirs_decay: Option<f64>,
     filter: F,
 ) -> Result<Vec<MatchEntry>, Box<dyn Error>>
 where
@@ -56,6 +105,7 @@ where
     let highest_count = highest_count_entry.noted_count;
 
     let denylist_matcher = config::get_denylist_matcher();
+    let scorer = frecency::get_scorer();
     let missing_files_delete_after_secs: i64 =
         i64::from(config::get_missing_files_delete_from_db_after()) * 86400;
 
@@ -101,44 +151,50 @@ where
                 return Outcome::Skip;
             }
 
-            match filter(&row, &meta) {
-                FilterResult::Exclude => return Outcome::Skip,
-                FilterResult::Include => {}
-            }
-
-            let frecency = frecency::calculate(
-                row.noted_count,
-                timestamp_age_hours(now, row.last_noted_timestamp),
+            let frecency = scorer.score(&frecency::FrecencyInput {
+                count: row.noted_count,
+                last_noted_age_hours: timestamp_age_hours(now, row.last_noted_timestamp),
                 highest_count,
-                oldest_last_noted_timestamp_hours,
-            );
+                oldest_last_noted_age_hours: oldest_last_noted_timestamp_hours,
+            });
 
-            Outcome::Match(MatchEntry {
+            Outcome::Match(Box::new(MatchEntry {
                 table_paths_entry: row,
                 metadata: meta,
                 frecency,
-            })
+            }))
         })
         .collect();
 
-    let mut matches = vec![];
+    let mut candidates = vec![];
     let mut to_delete = vec![];
 
     for outcome in outcomes {
         match outcome {
-            Outcome::Match(entry) => matches.push(entry),
+            Outcome::Match(entry) => candidates.push(*entry),
             Outcome::Delete(path) => to_delete.push(path),
             Outcome::Skip => {}
         }
     }
 
-    if !to_delete.is_empty() {
-        let placeholders = to_delete.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
-        let sql = format!("DELETE FROM paths WHERE path IN ({placeholders})");
-        conn.execute(&sql, params_from_iter(&to_delete))
-            .expect("Deleting paths from DB failed");
+    db::delete_paths(conn, &to_delete).expect("Deleting paths from DB failed");
+
+    if let Some(decay) = derive_dirs_decay {
+        add_derived_dirs(&mut candidates, decay, &denylist_matcher);
     }
 
+    // Filtered after scoring, so files that are filtered out still pass frecency to their
+    // directories
+    let mut matches: Vec<MatchEntry> = candidates
+        .into_par_iter()
+        .filter(|m| {
+            matches!(
+                filter(&m.table_paths_entry, &m.metadata),
+                FilterResult::Include
+            )
+        })
+        .collect();
+
     matches.par_sort_unstable_by_key(|e| e.frecency.to_bits());
 
     Ok(matches)
@@ -1,3 +1,45 @@
+use core::error::Error;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use regex::RegexSet;
+
+use super::path::expand_tilde_in_path;
+
+/// Builds a matcher for gitignore-style `globs`, the same syntax as the denylist, with `~`
+/// expanded in each. Returns `None` if there are no globs.
+pub fn build_glob_matcher(globs: &[String]) -> Result<Option<Gitignore>, Box<dyn Error>> {
+    if globs.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GitignoreBuilder::new("/");
+    for glob in globs {
+        builder
+            .add_line(None, &expand_tilde_in_path(glob).to_string_lossy())
+            .map_err(|err| format!("Invalid glob '{glob}': {err}"))?;
+    }
+
+    Ok(Some(builder.build()?))
+}
+
+/// Returns true if `path`, or any directory it's in, matches `matcher`.
+#[must_use]
+pub fn matches_glob(matcher: &Gitignore, path: &str, is_dir: bool) -> bool {
+    matcher
+        .matched_path_or_any_parents(path, is_dir)
+        .is_ignore()
+}
+
+/// Builds a set matching any of `regexes`, or `None` if there are none.
+pub fn build_regex_set(regexes: &[String]) -> Result<Option<RegexSet>, Box<dyn Error>> {
+    if regexes.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        RegexSet::new(regexes).map_err(|err| format!("Invalid regex: {err}"))?,
+    ))
+}
+
 /// Returns true if `path` matches all `keywords` using the zoxide matching algorithm:
 /// * All terms must be present within the path, in order.
 /// * The last component of the last keyword must be contained in the last component of the path.
@@ -30,6 +72,94 @@ pub fn matches_zoxide_algo(path: &str, keywords: &[String]) -> bool {
     path_last_component.contains(kw_last_component)
 }
 
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+const BONUS_COMPONENT_START: i32 = 9;
+const BONUS_WORD_START: i32 = 8;
+const BONUS_CONSECUTIVE: i32 = 4;
+
+/// The bonus for matching the character at `index`, which depends on what comes before it: the
+/// start of a path component scores highest, then the start of a word within one.
+fn position_bonus(text: &[char], index: usize) -> i32 {
+    match index.checked_sub(1).map(|prev| text[prev]) {
+        None | Some('/') => BONUS_COMPONENT_START,
+        Some('-' | '_' | '.' | ' ') => BONUS_WORD_START,
+        Some(_) => 0,
+    }
+}
+
+/// Returns a score between 0 and 1 if the characters of `keywords` all appear in `path` in order
+/// (case-insensitively, but not necessarily next to each other), fzf-style, or `None` if they
+/// don't. Matches at the start of path components or words, and runs of consecutive characters,
+/// score higher; gaps between matched characters score lower. The best-scoring alignment is
+/// used, and a score of 1 means every character continued a run from a component start.
+#[must_use]
+pub fn fuzzy_match_score(path: &str, keywords: &[String]) -> Option<f64> {
+    let pattern: Vec<char> = keywords
+        .iter()
+        .flat_map(|keyword| keyword.to_lowercase().chars().collect::<Vec<_>>())
+        .collect();
+    if pattern.is_empty() {
+        return Some(1.0);
+    }
+
+    let text: Vec<char> = path.to_lowercase().chars().collect();
+    let bonuses: Vec<i32> = (0..text.len())
+        .map(|index| position_bonus(&text, index))
+        .collect();
+
+    // For each pattern character in turn, the best (score, bonus of the run it's part of) with
+    // that character matched at each position in the text, as in Smith-Waterman with affine gaps
+    let mut previous_row: Vec<Option<(i32, i32)>> = vec![None; text.len()];
+    for (pattern_index, &pattern_char) in pattern.iter().enumerate() {
+        let mut row: Vec<Option<(i32, i32)>> = vec![None; text.len()];
+        let mut best_after_gap: Option<i32> = None;
+
+        for (text_index, &text_char) in text.iter().enumerate() {
+            if text_index >= 2 {
+                let gap_opened =
+                    previous_row[text_index - 2].map(|(score, _)| score + SCORE_GAP_START);
+                let gap_extended = best_after_gap.map(|score| score + SCORE_GAP_EXTENSION);
+                best_after_gap = gap_opened.max(gap_extended);
+            }
+
+            if text_char != pattern_char {
+                continue;
+            }
+
+            let bonus = bonuses[text_index];
+            let after_gap = if pattern_index == 0 {
+                Some((SCORE_MATCH + bonus, bonus))
+            } else {
+                best_after_gap.map(|score| (score + SCORE_MATCH + bonus, bonus))
+            };
+            let consecutive = text_index
+                .checked_sub(1)
+                .and_then(|prev| previous_row[prev])
+                .filter(|_| pattern_index > 0)
+                .map(|(score, previous_run_bonus)| {
+                    let run_bonus = previous_run_bonus.max(bonus).max(BONUS_CONSECUTIVE);
+                    (score + SCORE_MATCH + run_bonus, run_bonus)
+                });
+
+            row[text_index] = after_gap.max(consecutive);
+        }
+
+        previous_row = row;
+    }
+
+    let best = previous_row
+        .iter()
+        .flatten()
+        .map(|(score, _)| *score)
+        .max()?;
+    let pattern_len = i32::try_from(pattern.len()).unwrap_or(i32::MAX);
+    let perfect = pattern_len.saturating_mul(SCORE_MATCH + BONUS_COMPONENT_START);
+
+    Some((f64::from(best) / f64::from(perfect)).clamp(0.0, 1.0))
+}
+
 #[allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
 #[cfg(test)]
 mod tests {
@@ -112,7 +242,102 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_glob_matches_gitignore_style() {
+        let matcher = build_glob_matcher(&["*.tf".to_owned(), "/infra/".to_owned()])
+            .unwrap()
+            .unwrap();
+        assert!(matches_glob(&matcher, "/home/user/main.tf", false));
+        assert!(matches_glob(&matcher, "/infra/modules/readme.md", false));
+        assert!(!matches_glob(&matcher, "/home/user/main.tfvars", false));
+        assert!(!matches_glob(&matcher, "/home/infra", false));
+    }
+
+    #[test]
+    fn test_glob_matcher_none_without_globs() {
+        assert!(build_glob_matcher(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_regex_set_invalid() {
+        assert!(build_regex_set(&["(unclosed".to_owned()]).is_err());
+        assert!(build_regex_set(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_empty_keywords() {
+        assert_eq!(fuzzy_match_score("/foo/bar", &[]), Some(1.0));
+    }
+
+    #[test]
+    fn test_fuzzy_matches_abbreviation() {
+        assert!(fuzzy_match_score("/home/user/memy/config", &["mmycfg".to_owned()]).is_some());
+        assert!(matches!(
+            fuzzy_match_score("/home/user/memy/config", &["memy".to_owned(), "cfg".to_owned()]),
+            Some(score) if score > 0.0
+        ));
+    }
+
+    #[test]
+    fn test_fuzzy_requires_order() {
+        assert_eq!(fuzzy_match_score("/foo/bar", &["bf".to_owned()]), None);
+        assert_eq!(fuzzy_match_score("/foo/bar", &["fooz".to_owned()]), None);
+    }
+
+    #[test]
+    fn test_fuzzy_case_insensitive() {
+        assert!(fuzzy_match_score("/FOO/BAR", &["fb".to_owned()]).is_some());
+        assert!(fuzzy_match_score("/foo/bar", &["FB".to_owned()]).is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_exact_component_scores_one() {
+        assert_eq!(
+            fuzzy_match_score("/src/memy", &["memy".to_owned()]),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_prefers_consecutive_matches() {
+        let keywords = ["conf".to_owned()];
+        let consecutive = fuzzy_match_score("/home/config", &keywords).unwrap();
+        let scattered = fuzzy_match_score("/home/cxoxnxf", &keywords).unwrap();
+        assert!(consecutive > scattered, "{consecutive} <= {scattered}");
+    }
+
+    #[test]
+    fn test_fuzzy_prefers_component_starts() {
+        let keywords = ["mc".to_owned()];
+        let boundaries = fuzzy_match_score("/memy/config", &keywords).unwrap();
+        let middle = fuzzy_match_score("/xmemyxconfig", &keywords).unwrap();
+        assert!(boundaries > middle, "{boundaries} <= {middle}");
+    }
+
+    #[test]
+    fn test_fuzzy_uses_best_alignment() {
+        // The first 'c' is a poor match, but the one starting "config" should be used
+        let keywords = ["config".to_owned()];
+        assert_eq!(fuzzy_match_score("/acx/config", &keywords), Some(1.0));
+    }
+
     proptest! {
+        #[test]
+        fn prop_fuzzy_matches_any_subsequence(
+            path in "/[a-z]{1,8}/[a-z_.-]{1,12}",
+            mask in proptest::collection::vec(any::<bool>(), 21),
+        ) {
+            let keyword: String = path
+                .chars()
+                .zip(mask.iter())
+                .filter_map(|(c, keep)| keep.then_some(c))
+                .collect();
+            let score = fuzzy_match_score(&path, core::slice::from_ref(&keyword));
+            prop_assert!(
+                matches!(score, Some(s) if (0.0..=1.0).contains(&s)),
+                "path={path} keyword={keyword} score={score:?}"
+            );
+        }
         #[test]
         fn prop_keyword_in_last_component_matches(
             prefix in "[a-z]{1,8}",
@@ -0,0 +1,163 @@
+use core::error::Error;
+
+/// A part of a parsed [`Template`].
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field {
+        name: String,
+        modifier: Option<String>,
+    },
+}
+
+/// An output template like `{path}\t{last_noted:relative}`: `{field}` or `{field:modifier}`
+/// placeholders between literal text, where `{{` and `}}` are literal braces and `\t`, `\n` and
+/// `\\` are escapes (so templates can be written in single-quoted shell strings).
+#[derive(Debug, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+fn push_literal(segments: &mut Vec<Segment>, c: char) {
+    if let Some(Segment::Literal(literal)) = segments.last_mut() {
+        literal.push(c);
+    } else {
+        segments.push(Segment::Literal(c.to_string()));
+    }
+}
+
+impl Template {
+    /// Parses `template`, checking every placeholder names one of `fields`.
+    pub fn parse(template: &str, fields: &[&str]) -> Result<Self, Box<dyn Error>> {
+        let mut segments = Vec::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('t') => push_literal(&mut segments, '\t'),
+                    Some('n') => push_literal(&mut segments, '\n'),
+                    Some('\\') | None => push_literal(&mut segments, '\\'),
+                    Some(other) => {
+                        push_literal(&mut segments, '\\');
+                        push_literal(&mut segments, other);
+                    }
+                },
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    push_literal(&mut segments, '{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    push_literal(&mut segments, '}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or_else(|| format!("Unclosed '{{' in template: {template}"))?;
+                    let placeholder = &rest[..end];
+                    let (name, modifier) = placeholder
+                        .split_once(':')
+                        .map_or((placeholder, None), |(name, modifier)| {
+                            (name, Some(modifier.to_owned()))
+                        });
+
+                    if !fields.contains(&name) {
+                        return Err(format!(
+                            "Unknown template field '{name}', expected one of: {}",
+                            fields.join(", ")
+                        )
+                        .into());
+                    }
+
+                    segments.push(Segment::Field {
+                        name: name.to_owned(),
+                        modifier,
+                    });
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => return Err(format!("Unmatched '}}' in template: {template}").into()),
+                _ => push_literal(&mut segments, c),
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Returns the `(field, modifier)` of every placeholder, in order.
+    pub fn placeholders(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Field { name, modifier } => Some((name.as_str(), modifier.as_deref())),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Renders the template, getting the value of each placeholder from `value`.
+    pub fn render(&self, value: impl Fn(&str, Option<&str>) -> String) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.clone(),
+                Segment::Field { name, modifier } => value(name, modifier.as_deref()),
+            })
+            .collect()
+    }
+}
+
+#[allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+#[allow(
+    clippy::literal_string_with_formatting_args,
+    reason = "Templates use the same brace syntax"
+)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const FIELDS: [&str; 3] = ["path", "count", "last_noted"];
+
+    fn render_debug(template: &str) -> String {
+        Template::parse(template, &FIELDS)
+            .unwrap()
+            .render(|name, modifier| format!("<{name}|{}>", modifier.unwrap_or("-")))
+    }
+
+    #[test]
+    fn test_template_fields_and_modifiers() {
+        assert_eq!(
+            render_debug("{path}: {last_noted:relative}"),
+            "<path|->: <last_noted|relative>"
+        );
+    }
+
+    #[test]
+    fn test_template_escapes() {
+        assert_eq!(render_debug(r"{path}\t{count}\n"), "<path|->\t<count|->\n");
+        assert_eq!(render_debug(r"a\\b\x"), r"a\b\x");
+        assert_eq!(render_debug("{{path}}"), "{path}");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert!(Template::parse("{nope}", &FIELDS).is_err());
+        assert!(Template::parse("{path", &FIELDS).is_err());
+        assert!(Template::parse("path}", &FIELDS).is_err());
+    }
+
+    #[test]
+    fn test_template_placeholders() {
+        let template = Template::parse("{path} {count:x}", &FIELDS).unwrap();
+        assert_eq!(
+            template.placeholders().collect::<Vec<_>>(),
+            vec![("path", None), ("count", Some("x"))]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_literal_text_renders_unchanged(text in "[^{}\\\\]*") {
+            prop_assert_eq!(render_debug(&text), text);
+        }
+    }
+}
@@ -36,9 +36,38 @@ pub fn timestamp_age_hours(now: UnixTimestamp, timestamp: UnixTimestamp) -> Unix
     age_seconds as f64 / 3600.0
 }
 
+/// Formats how long before `now` `timestamp` was in the largest whole unit, e.g. "3h ago".
+#[must_use]
+pub fn format_relative(now: UnixTimestamp, timestamp: UnixTimestamp) -> String {
+    const UNITS: [(UnixTimestamp, &str); 6] = [
+        (365 * 86400, "y"),
+        (30 * 86400, "mo"),
+        (7 * 86400, "w"),
+        (86400, "d"),
+        (3600, "h"),
+        (60, "m"),
+    ];
+
+    let age_seconds = now - timestamp;
+    UNITS
+        .iter()
+        .find(|(unit_seconds, _)| age_seconds >= *unit_seconds)
+        .map_or_else(
+            || "just now".to_owned(),
+            |(unit_seconds, suffix)| format!("{}{suffix} ago", age_seconds / unit_seconds),
+        )
+}
+
 pub fn parse_newer_than(input: &str) -> Result<UnixTimestamp, Box<dyn core::error::Error>> {
+    parse_newer_than_at(input, get_timestamp_now())
+}
+
+/// Like [`parse_newer_than`], with durations counted back from `now`.
+fn parse_newer_than_at(
+    input: &str,
+    now: UnixTimestamp,
+) -> Result<UnixTimestamp, Box<dyn core::error::Error>> {
     if let Ok(duration) = humantime::parse_duration(input) {
-        let now = get_timestamp_now();
         let cutoff = now - duration.as_secs().cast_signed() as UnixTimestamp;
         return Ok(cutoff);
     }
@@ -71,12 +100,96 @@ pub fn parse_newer_than(input: &str) -> Result<UnixTimestamp, Box<dyn core::erro
     Err(format!("Unable to parse '{input}' as a duration or date/time").into())
 }
 
+/// Parses a `A..B` time range, where each side is anything [`parse_newer_than`] accepts and either
+/// may be left out for an open-ended range. Returns the earlier and later timestamps, in that
+/// order whichever way round they were given.
+pub fn parse_between(
+    input: &str,
+) -> Result<(Option<UnixTimestamp>, Option<UnixTimestamp>), Box<dyn core::error::Error>> {
+    parse_between_at(input, get_timestamp_now())
+}
+
+/// Like [`parse_between`], with durations on both sides counted back from the same `now`.
+fn parse_between_at(
+    input: &str,
+    now: UnixTimestamp,
+) -> Result<(Option<UnixTimestamp>, Option<UnixTimestamp>), Box<dyn core::error::Error>> {
+    let (start, end) = input
+        .split_once("..")
+        .ok_or_else(|| format!("Expected a range like 'A..B', got '{input}'"))?;
+
+    let parse_side = |side: &str| {
+        let trimmed = side.trim();
+        (!trimmed.is_empty())
+            .then(|| parse_newer_than_at(trimmed, now))
+            .transpose()
+    };
+
+    match (parse_side(start)?, parse_side(end)?) {
+        (None, None) => {
+            Err(format!("At least one end of the range '{input}' must be given").into())
+        }
+        (Some(a), Some(b)) => Ok((Some(a.min(b)), Some(a.max(b)))),
+        open => Ok(open),
+    }
+}
+
+#[allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::DateTime;
     use proptest::prelude::*;
 
+    #[test]
+    fn test_format_relative() {
+        let now = 1_000_000_000;
+        assert_eq!(format_relative(now, now), "just now");
+        assert_eq!(format_relative(now, now - 59), "just now");
+        assert_eq!(format_relative(now, now - 60), "1m ago");
+        assert_eq!(format_relative(now, now - 3 * 3600 - 120), "3h ago");
+        assert_eq!(format_relative(now, now - 2 * 86400), "2d ago");
+        assert_eq!(format_relative(now, now - 15 * 86400), "2w ago");
+        assert_eq!(format_relative(now, now - 65 * 86400), "2mo ago");
+        assert_eq!(format_relative(now, now - 800 * 86400), "2y ago");
+        // Clock skew shouldn't produce negative ages
+        assert_eq!(format_relative(now, now + 100), "just now");
+    }
+
+    #[test]
+    fn test_parse_between_dates() {
+        let (start, end) = parse_between("2025-01-01..2025-02-01").unwrap();
+        assert_eq!(start, Some(parse_newer_than("2025-01-01").unwrap()));
+        assert_eq!(end, Some(parse_newer_than("2025-02-01").unwrap()));
+    }
+
+    #[test]
+    fn test_parse_between_durations_either_order() {
+        let now = get_timestamp_now();
+        let (start, end) = parse_between_at("1d..7d", now).unwrap();
+        assert_eq!(start, Some(now - 7 * 86400));
+        assert_eq!(end, Some(now - 86400));
+        assert_eq!(parse_between_at("7d..1d", now).unwrap(), (start, end));
+    }
+
+    #[test]
+    fn test_parse_between_open_ended() {
+        let (start, end) = parse_between("2025-01-01..").unwrap();
+        assert_eq!(start, Some(parse_newer_than("2025-01-01").unwrap()));
+        assert_eq!(end, None);
+
+        let (open_start, until) = parse_between("..3d").unwrap();
+        assert_eq!(open_start, None);
+        assert!(until.is_some());
+    }
+
+    #[test]
+    fn test_parse_between_invalid() {
+        assert!(parse_between("2025-01-01").is_err());
+        assert!(parse_between("..").is_err());
+        assert!(parse_between("yesterday..today").is_err());
+    }
+
     #[test]
     fn test_parse_newer_than_humantime_hour() {
         let now = get_timestamp_now();
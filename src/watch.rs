@@ -0,0 +1,242 @@
+use core::error::Error;
+use ignore::gitignore::Gitignore;
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Instant;
+use tracing::{debug, info, instrument, warn};
+
+use crate::note;
+use crate::utils::cli::WatchArgs;
+use crate::utils::config;
+use crate::utils::db;
+use crate::utils::path::expand_tilde_in_path;
+use crate::utils::time::get_timestamp_now;
+
+/// Build output, dependency and cache directories, whose files are rarely worth noting.
+const DEFAULT_EXCLUDED_DIRS: &[&str] = &[
+    ".cache",
+    ".git",
+    ".venv",
+    "__pycache__",
+    "build",
+    "dist",
+    "node_modules",
+    "target",
+];
+
+const EVENT_BUFFER_SIZE: usize = 4096;
+
+/// Watches a set of directory trees, adding watches for subdirectories as they appear.
+struct Watcher {
+    inotify: Inotify,
+    dirs: HashMap<WatchDescriptor, PathBuf>,
+    excluded_names: Vec<String>,
+    denylist_matcher: Gitignore,
+    file_events: EventMask,
+}
+
+impl Watcher {
+    fn is_excluded(&self, dir: &Path) -> bool {
+        dir.file_name().is_some_and(|name| {
+            self.excluded_names
+                .iter()
+                .any(|excluded| name == excluded.as_str())
+        }) || self
+            .denylist_matcher
+            .matched_path_or_any_parents(dir, true)
+            .is_ignore()
+    }
+
+    /// Watches `dir` and those of its subdirectories that aren't excluded.
+    fn watch_tree(&mut self, dir: &Path) {
+        let mask = WatchMask::CLOSE_WRITE
+            | WatchMask::CLOSE_NOWRITE
+            | WatchMask::MOVED_TO
+            | WatchMask::CREATE;
+        match self.inotify.watches().add(dir, mask) {
+            Ok(wd) => {
+                self.dirs.insert(wd, dir.to_path_buf());
+            }
+            Err(err) => {
+                warn!("Cannot watch {}: {err}", dir.display());
+                return;
+            }
+        }
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let subdir = entry.path();
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+                && !self.is_excluded(&subdir)
+            {
+                self.watch_tree(&subdir);
+            }
+        }
+    }
+
+    /// Sends files to note to `sender` as events arrive, until the receiver goes away.
+    fn run(mut self, sender: &mpsc::Sender<PathBuf>) -> std::io::Result<()> {
+        let mut buffer = [0; EVENT_BUFFER_SIZE];
+        loop {
+            for event in self.inotify.read_events_blocking(&mut buffer)? {
+                if event.mask.contains(EventMask::Q_OVERFLOW) {
+                    warn!("Too many filesystem events at once, some files may not be noted");
+                    continue;
+                }
+                if event.mask.contains(EventMask::IGNORED) {
+                    self.dirs.remove(&event.wd);
+                    continue;
+                }
+
+                let (Some(dir), Some(name)) = (self.dirs.get(&event.wd), event.name) else {
+                    continue;
+                };
+                let path = dir.join(name);
+
+                if event.mask.contains(EventMask::ISDIR) {
+                    if event
+                        .mask
+                        .intersects(EventMask::CREATE | EventMask::MOVED_TO)
+                        && !self.is_excluded(&path)
+                    {
+                        self.watch_tree(&path);
+                    }
+                } else if event.mask.intersects(self.file_events) && sender.send(path).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn note_paths(
+    conn: &mut Connection,
+    paths: &[PathBuf],
+    denylist_matcher: &Gitignore,
+    source: &str,
+) -> Result<(), Box<dyn Error>> {
+    let now = get_timestamp_now();
+    let tx = conn.transaction()?;
+
+    for path in paths {
+        // Missing and denied files are common here (temporary files, caches), so aren't warned about
+        match note::preprocess_path(&path.to_string_lossy(), denylist_matcher) {
+            Ok(Ok(clean_path)) => note::insert_path(&tx, &clean_path, now, Some(source), None)?,
+            Ok(Err(skip)) => debug!("Not noting {}: {skip:?}", path.display()),
+            Err(err) => warn!("Cannot note {}: {err}", path.display()),
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Removes and returns the paths that have had no activity for `debounce`.
+fn take_settled(
+    pending: &mut HashMap<PathBuf, Instant>,
+    debounce: core::time::Duration,
+) -> Vec<PathBuf> {
+    let settled: Vec<PathBuf> = pending
+        .iter()
+        .filter(|(_, last_event)| last_event.elapsed() >= debounce)
+        .map(|(path, _)| path.clone())
+        .collect();
+    for path in &settled {
+        pending.remove(path);
+    }
+    settled
+}
+
+#[instrument(level = "trace")]
+pub fn command(args: &WatchArgs) -> Result<(), Box<dyn Error>> {
+    let debounce = humantime::parse_duration(&args.debounce)
+        .map_err(|err| format!("Invalid --debounce '{}': {err}", args.debounce))?;
+    let denylist_matcher = config::get_denylist_matcher();
+
+    let mut excluded_names: Vec<String> = if args.no_default_excludes {
+        Vec::new()
+    } else {
+        DEFAULT_EXCLUDED_DIRS
+            .iter()
+            .map(|&name| name.to_owned())
+            .collect()
+    };
+    excluded_names.extend(args.exclude.iter().cloned());
+
+    let mut file_events = EventMask::CLOSE_WRITE | EventMask::MOVED_TO;
+    if !args.writes_only {
+        file_events |= EventMask::CLOSE_NOWRITE;
+    }
+
+    let mut watcher = Watcher {
+        inotify: Inotify::init()?,
+        dirs: HashMap::new(),
+        excluded_names,
+        denylist_matcher: denylist_matcher.clone(),
+        file_events,
+    };
+    for dir in &args.dirs {
+        let path = fs::canonicalize(expand_tilde_in_path(dir))
+            .map_err(|err| format!("Cannot watch {dir}: {err}"))?;
+        if !path.is_dir() {
+            return Err(format!("Cannot watch {dir}: not a directory").into());
+        }
+        watcher.watch_tree(&path);
+    }
+    info!("Watching {} directories", watcher.dirs.len());
+
+    // Writing the database itself mustn't be noted, or each note would cause another
+    let db_file = db::get_db_file_path();
+    let db_dir = db_file
+        .parent()
+        .map(|dir| fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf()));
+    let mut db_connection = db::open()?;
+
+    let (sender, receiver) = mpsc::channel();
+    let reader = thread::spawn(move || watcher.run(&sender));
+
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    loop {
+        let next_file = pending.values().min().map_or_else(
+            || receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            |oldest| receiver.recv_timeout(debounce.saturating_sub(oldest.elapsed())),
+        );
+        match next_file {
+            Ok(path) => {
+                if !db_dir.as_ref().is_some_and(|dir| path.starts_with(dir)) {
+                    pending.insert(path, Instant::now());
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let settled = take_settled(&mut pending, debounce);
+        if !settled.is_empty()
+            && let Err(err) = note_paths(
+                &mut db_connection,
+                &settled,
+                &denylist_matcher,
+                &args.source,
+            )
+        {
+            // Often the database being briefly locked, so try again once they settle again
+            warn!("Cannot note {} files, will retry: {err}", settled.len());
+            for path in settled {
+                pending.entry(path).or_insert_with(Instant::now);
+            }
+        }
+    }
+
+    reader
+        .join()
+        .map_err(|_| "The filesystem watcher stopped unexpectedly")??;
+    Ok(())
+}
@@ -17,9 +17,31 @@ pub fn command(args: &ZArgs) -> Result<(), Box<dyn Error>> {
         limit_results: if args.interactive { None } else { Some(1) },
         head: None,
         sort: None,
+        cwd_boost: None,
+        match_mode: None,
+        derive_dirs: false,
+        sources: vec![],
+        tags: vec![],
+        globs: vec![],
+        exclude_globs: vec![],
+        regexes: vec![],
+        extensions: vec![],
+        kinds: vec![],
+        sniff: false,
+        under: None,
+        repo: false,
+        relative: false,
         pretty_paths: false,
         format: "plain".to_owned(),
+        template: None,
+        null_separated: false,
+        long: false,
         newer_than: None,
+        older_than: None,
+        between: None,
+        min_count: None,
+        max_count: None,
+        min_score: None,
     };
     crate::list::command(&list_args)
 }
@@ -0,0 +1,138 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+fn list_in_dir(ctx: &TestContext, current_dir: &std::path::Path, args: &[&str]) -> Vec<String> {
+    let mut full_args = vec!["list", "--config", "import_on_first_use=false"];
+    full_args.extend(args);
+
+    let output = memy_cmd_in_dir(Some(&ctx.db_path), None, current_dir, &full_args);
+    assert!(
+        output.status.success(),
+        "memy list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+#[test]
+fn test_cwd_boost_ranks_paths_under_cwd_first() {
+    let ctx = TestContext::new();
+
+    let project_dir = create_test_directory(&ctx.working_path, "project");
+    let elsewhere_dir = create_test_directory(&ctx.working_path, "elsewhere");
+    let project_file = create_test_file(&project_dir, "main.rs", "fn main() {}");
+    let elsewhere_file = create_test_file(&elsewhere_dir, "notes.txt", "notes");
+
+    note_path(
+        &ctx.db_path,
+        None,
+        project_file.to_str().unwrap(),
+        2,
+        &[],
+        &[],
+    );
+    note_path(
+        &ctx.db_path,
+        None,
+        elsewhere_file.to_str().unwrap(),
+        3,
+        &[],
+        &[],
+    );
+
+    let unboosted = list_in_dir(
+        &ctx,
+        &project_dir,
+        &["--files-only", "--config", "recency_bias=0"],
+    );
+    assert_path_before(&unboosted, "notes.txt", "main.rs");
+
+    let boosted = list_in_dir(
+        &ctx,
+        &project_dir,
+        &[
+            "--files-only",
+            "--config",
+            "recency_bias=0",
+            "--cwd-boost",
+            "10",
+        ],
+    );
+    assert_path_before(&boosted, "main.rs", "notes.txt");
+}
+
+#[test]
+fn test_cwd_boost_from_config() {
+    let ctx = TestContext::new();
+
+    let project_dir = create_test_directory(&ctx.working_path, "project");
+    let elsewhere_dir = create_test_directory(&ctx.working_path, "elsewhere");
+    let project_file = create_test_file(&project_dir, "main.rs", "fn main() {}");
+    let elsewhere_file = create_test_file(&elsewhere_dir, "notes.txt", "notes");
+
+    note_path(
+        &ctx.db_path,
+        None,
+        project_file.to_str().unwrap(),
+        2,
+        &[],
+        &[],
+    );
+    note_path(
+        &ctx.db_path,
+        None,
+        elsewhere_file.to_str().unwrap(),
+        3,
+        &[],
+        &[],
+    );
+
+    let boosted = list_in_dir(
+        &ctx,
+        &project_dir,
+        &[
+            "--files-only",
+            "--config",
+            "recency_bias=0",
+            "--config",
+            "cwd_boost=10",
+        ],
+    );
+    assert_path_before(&boosted, "main.rs", "notes.txt");
+
+    let overridden = list_in_dir(
+        &ctx,
+        &project_dir,
+        &[
+            "--files-only",
+            "--config",
+            "recency_bias=0",
+            "--config",
+            "cwd_boost=10",
+            "--cwd-boost",
+            "1",
+        ],
+    );
+    assert_path_before(&overridden, "notes.txt", "main.rs");
+}
+
+#[test]
+fn test_cwd_boost_negative_rejected() {
+    let ctx = TestContext::new();
+
+    let output = memy_cmd_in_dir(
+        Some(&ctx.db_path),
+        None,
+        &ctx.working_path,
+        &["list", "--cwd-boost=-1"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("must not be negative"));
+}
@@ -0,0 +1,247 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use std::io::{BufRead as _, BufReader};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+
+/// A `memy daemon` running in the background, stopped when dropped.
+struct Daemon {
+    child: Child,
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _: std::io::Result<()> = self.child.kill();
+        let _: std::io::Result<ExitStatus> = self.child.wait();
+    }
+}
+
+fn daemon_command(db_path: &Path, ctx: &TestContext, runtime_dir: &Path) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("memy"));
+    cmd.env("MEMY_DB_DIR", db_path)
+        .env("MEMY_CONFIG_DIR", &ctx.config_path)
+        .env("XDG_RUNTIME_DIR", runtime_dir)
+        .args(["--config", "import_on_first_use=false", "daemon"]);
+    cmd
+}
+
+fn start_daemon(ctx: &TestContext, runtime_dir: &Path) -> Daemon {
+    let daemon = Daemon {
+        child: daemon_command(&ctx.db_path, ctx, runtime_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap(),
+    };
+
+    let socket = runtime_dir.join("memy").join("memy.sock");
+    for _ in 0..100 {
+        if socket.exists() {
+            return daemon;
+        }
+        sleep(50);
+    }
+    panic!("memy daemon did not create {}", socket.display());
+}
+
+/// Runs `memy note` from the working directory with debug logging, returning the log output.
+fn note(db_path: &Path, ctx: &TestContext, runtime_dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("memy"))
+        .env("MEMY_DB_DIR", db_path)
+        .env("MEMY_CONFIG_DIR", &ctx.config_path)
+        .env("XDG_RUNTIME_DIR", runtime_dir)
+        .env_remove("RUST_LOG")
+        .current_dir(&ctx.working_path)
+        .args(["-vv", "--config", "import_on_first_use=false"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "note failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stderr).unwrap()
+}
+
+fn noted_count(db_path: &Path, path: &Path) -> String {
+    let output = memy_cmd_test_defaults(db_path, None, &["explain", path.to_str().unwrap()]);
+    if !output.status.success() {
+        return "not noted".to_owned();
+    }
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Noted count: ").map(str::to_owned))
+        .unwrap()
+}
+
+fn setup() -> (TestContext, PathBuf, PathBuf) {
+    let ctx = TestContext::new();
+    let runtime_dir = ctx.cache_path.join("runtime");
+    std::fs::create_dir(&runtime_dir).unwrap();
+    let file = create_test_file(&ctx.working_path, "a.txt", "a");
+    (ctx, runtime_dir, file)
+}
+
+#[test]
+fn test_note_forwards_to_daemon() {
+    let (ctx, runtime_dir, file) = setup();
+    let _daemon = start_daemon(&ctx, &runtime_dir);
+
+    let absolute = note(
+        &ctx.db_path,
+        &ctx,
+        &runtime_dir,
+        &["note", file.to_str().unwrap()],
+    );
+    assert!(
+        absolute.contains("Noted through the memy daemon"),
+        "{absolute}"
+    );
+
+    // Relative paths are resolved against the client's directory, not the daemon's
+    let relative = note(&ctx.db_path, &ctx, &runtime_dir, &["note", "a.txt"]);
+    assert!(
+        relative.contains("Noted through the memy daemon"),
+        "{relative}"
+    );
+
+    assert_eq!(noted_count(&ctx.db_path, &file), "2");
+}
+
+#[test]
+fn test_note_without_daemon_notes_directly() {
+    let (ctx, runtime_dir, file) = setup();
+
+    let log = note(
+        &ctx.db_path,
+        &ctx,
+        &runtime_dir,
+        &["note", file.to_str().unwrap()],
+    );
+    assert!(log.contains("Noting directly"), "{log}");
+    assert_eq!(noted_count(&ctx.db_path, &file), "1");
+}
+
+#[test]
+fn test_daemon_refuses_other_databases() {
+    let (ctx, runtime_dir, file) = setup();
+    let _daemon = start_daemon(&ctx, &runtime_dir);
+    let (_other_dir, other_db_path) = temp_dir();
+
+    let log = note(
+        &other_db_path,
+        &ctx,
+        &runtime_dir,
+        &["note", file.to_str().unwrap()],
+    );
+    assert!(log.contains("different database"), "{log}");
+    assert_eq!(noted_count(&other_db_path, &file), "1");
+    assert_eq!(noted_count(&ctx.db_path, &file), "not noted");
+}
+
+#[test]
+fn test_daemon_refuses_after_config_changes() {
+    let (ctx, runtime_dir, file) = setup();
+    let _daemon = start_daemon(&ctx, &runtime_dir);
+
+    create_config_file(&ctx.config_path, "denylist = [\"*.txt\"]\n");
+
+    let log = note(
+        &ctx.db_path,
+        &ctx,
+        &runtime_dir,
+        &["note", file.to_str().unwrap()],
+    );
+    assert!(log.contains("config file has changed"), "{log}");
+    assert!(log.contains("denied by denylist pattern"), "{log}");
+}
+
+#[test]
+fn test_daemon_skips_are_warned_by_client() {
+    let (ctx, runtime_dir, _) = setup();
+    let _daemon = start_daemon(&ctx, &runtime_dir);
+
+    let warned = note(&ctx.db_path, &ctx, &runtime_dir, &["note", "missing.txt"]);
+    assert!(warned.contains("Noted through the memy daemon"), "{warned}");
+    assert!(warned.contains("does not exist"), "{warned}");
+
+    // Warning options don't stop a client using the daemon, as the zsh hook sets them
+    let quiet = note(
+        &ctx.db_path,
+        &ctx,
+        &runtime_dir,
+        &[
+            "--config",
+            "missing_files_warn_on_note=false",
+            "note",
+            "missing.txt",
+        ],
+    );
+    assert!(quiet.contains("Noted through the memy daemon"), "{quiet}");
+    assert!(!quiet.contains("does not exist"), "{quiet}");
+}
+
+#[test]
+fn test_second_daemon_fails() {
+    let (ctx, runtime_dir, _) = setup();
+    let _daemon = start_daemon(&ctx, &runtime_dir);
+
+    let output = daemon_command(&ctx.db_path, &ctx, &runtime_dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already listening"));
+}
+
+#[test]
+fn test_daemon_batches_concurrent_notes() {
+    let (ctx, runtime_dir, file) = setup();
+    let _daemon = start_daemon(&ctx, &runtime_dir);
+
+    std::thread::scope(|scope| {
+        for _ in 0..10 {
+            scope.spawn(|| note(&ctx.db_path, &ctx, &runtime_dir, &["note", "a.txt"]));
+        }
+    });
+
+    assert_eq!(noted_count(&ctx.db_path, &file), "10");
+}
+
+#[test]
+fn test_note_fails_if_daemon_does_not_answer() {
+    let (ctx, runtime_dir, file) = setup();
+    let socket_dir = runtime_dir.join("memy");
+    std::fs::create_dir(&socket_dir).unwrap();
+    let listener = UnixListener::bind(socket_dir.join("memy.sock")).unwrap();
+
+    // Takes the request, then hangs up without saying whether it was noted
+    let fake_daemon = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        BufReader::new(&stream).read_line(&mut request).unwrap();
+    });
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("memy"))
+        .env("MEMY_DB_DIR", &ctx.db_path)
+        .env("MEMY_CONFIG_DIR", &ctx.config_path)
+        .env("XDG_RUNTIME_DIR", &runtime_dir)
+        .args(["--config", "import_on_first_use=false", "note"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    fake_daemon.join().unwrap();
+
+    assert!(!output.status.success(), "note should fail");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No answer from the memy daemon"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(noted_count(&ctx.db_path, &file), "not noted");
+}
@@ -72,3 +72,62 @@ fn test_db_migration_warns_for_first_10_terminal_list_runs() {
         "The 11th list run should not warn: {warning_seen:?}"
     );
 }
+
+#[test]
+fn test_db_migration_creates_synthetic_events() {
+    let ctx = TestContext::new();
+    create_v1_db(&ctx.db_path);
+
+    let test_file = create_test_file(&ctx.working_path, "test_file", "test content");
+    execute_sql(
+        &ctx.db_path,
+        &format!(
+            "INSERT INTO paths (path, noted_count, last_noted_timestamp) VALUES ('{}', 4, 1700000000)",
+            test_file.to_str().unwrap()
+        ),
+    );
+
+    let lines = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&lines, &[test_file.to_str().unwrap()]);
+
+    let conn = Connection::open(ctx.db_path.join("memy.sqlite3")).unwrap();
+    let (path, timestamp): (String, i64) = conn
+        .query_row("SELECT path, timestamp FROM events", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .expect("Expected exactly one synthetic event");
+    assert_eq!(path, test_file.to_str().unwrap());
+    assert_eq!(timestamp, 1_700_000_000);
+
+    let version: i32 = conn
+        .query_row("PRAGMA user_version;", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(
+        version, 5,
+        "Database should be migrated to the latest version"
+    );
+}
+
+#[test]
+fn test_note_records_events() {
+    let ctx = TestContext::new();
+
+    let test_file = create_test_file(&ctx.working_path, "test_file", "test content");
+    note_path(&ctx.db_path, None, test_file.to_str().unwrap(), 2, &[], &[]);
+
+    let conn = Connection::open(ctx.db_path.join("memy.sqlite3")).unwrap();
+    let mut stmt = conn
+        .prepare("SELECT path, cwd FROM events ORDER BY timestamp")
+        .unwrap();
+    let events: Vec<(String, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(events.len(), 2, "Each note should record an event");
+    for (path, cwd) in events {
+        assert_eq!(path, test_file.to_str().unwrap());
+        assert!(cwd.is_some(), "Events should record the working directory");
+    }
+}
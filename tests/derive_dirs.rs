@@ -0,0 +1,111 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+struct Layout {
+    ctx: TestContext,
+    project: String,
+    src: String,
+    other: String,
+}
+
+fn create_layout() -> Layout {
+    let ctx = TestContext::new();
+
+    let project = create_test_directory(&ctx.working_path, "project");
+    let src = create_test_directory(&project, "src");
+    let main_rs = create_test_file(&src, "main.rs", "a");
+    let other = create_test_directory(&ctx.working_path, "other");
+
+    note_path(&ctx.db_path, None, main_rs.to_str().unwrap(), 3, &[], &[]);
+    note_path(&ctx.db_path, None, other.to_str().unwrap(), 1, &[], &[]);
+
+    Layout {
+        project: project.to_str().unwrap().to_owned(),
+        src: src.to_str().unwrap().to_owned(),
+        other: other.to_str().unwrap().to_owned(),
+        ctx,
+    }
+}
+
+#[test]
+fn test_derive_dirs_off_by_default() {
+    let layout = create_layout();
+
+    let lines = list_paths(
+        &layout.ctx.db_path,
+        None,
+        &["--config", "recency_bias=0"],
+        &["-d"],
+    );
+    assert_lines_eq(&lines, &[&layout.other]);
+}
+
+#[test]
+fn test_derive_dirs_ranks_parent_directories() {
+    let layout = create_layout();
+
+    // main.rs scores 1, so src gets 0.5, beating other's 1/3, and project gets 0.25
+    let lines = list_paths(
+        &layout.ctx.db_path,
+        None,
+        &["--config", "recency_bias=0"],
+        &["-d", "--derive-dirs", "--limit-results", "3"],
+    );
+    assert_lines_eq(&lines, &[&layout.src, &layout.other, &layout.project]);
+}
+
+#[test]
+fn test_derive_dirs_from_config_with_decay() {
+    let layout = create_layout();
+
+    let lines = list_paths(
+        &layout.ctx.db_path,
+        None,
+        &[
+            "--config",
+            "recency_bias=0",
+            "--config",
+            "derive_dirs=true",
+            "--config",
+            "derive_dirs_decay=1.0",
+        ],
+        &["-d", "--", "project"],
+    );
+    assert_lines_eq(&lines, &[&layout.project]);
+}
+
+#[test]
+fn test_derive_dirs_json_count_is_zero() {
+    let layout = create_layout();
+
+    let output = memy_cmd_test_defaults(
+        &layout.ctx.db_path,
+        None,
+        &["list", "-d", "--derive-dirs", "--format", "json"],
+    );
+    assert!(output.status.success());
+    let results: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let src = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|result| result["path"] == layout.src.as_str())
+        .unwrap();
+    assert_eq!(src["count"], 0);
+}
+
+#[test]
+fn test_derive_dirs_invalid_decay() {
+    let layout = create_layout();
+
+    let output = memy_cmd_test_defaults(
+        &layout.ctx.db_path,
+        None,
+        &["--config", "derive_dirs_decay=0", "list", "--derive-dirs"],
+    );
+    assert!(!output.status.success());
+}
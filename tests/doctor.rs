@@ -0,0 +1,290 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use rusqlite::Connection;
+
+fn doctor(ctx: &TestContext, home: &std::path::Path, args: &[&str]) -> (bool, String) {
+    let mut full_args = vec!["--config", "import_on_first_use=false"];
+    full_args.extend(args);
+    full_args.push("doctor");
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &full_args,
+        vec![("HOME", home.to_str().unwrap()), ("ZDOTDIR", "")],
+    );
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+fn doctor_fix(ctx: &TestContext, home: &std::path::Path) -> String {
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["--config", "import_on_first_use=false", "doctor", "--fix"],
+        vec![("HOME", home.to_str().unwrap())],
+    );
+    assert!(
+        output.status.success(),
+        "doctor --fix failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn stored_paths(db_path: &std::path::Path) -> Vec<(String, u64)> {
+    let conn = Connection::open(db_path.join("memy.sqlite3")).unwrap();
+    let mut stmt = conn
+        .prepare("SELECT path, noted_count FROM paths ORDER BY path")
+        .unwrap();
+    stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn test_doctor_healthy_database() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    let file = create_test_file(&ctx.working_path, "file.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+
+    let (success, stdout) = doctor(&ctx, &home, &[]);
+    assert!(success, "doctor failed:\n{stdout}");
+    assert!(stdout.contains("[PASS] Database integrity: ok"), "{stdout}");
+    assert!(stdout.contains("[PASS] Schema version: 5"), "{stdout}");
+    assert!(stdout.contains("[PASS] Missing paths: none"), "{stdout}");
+    assert!(stdout.contains("[PASS] Duplicate paths: none"), "{stdout}");
+    assert!(stdout.contains("0 failures"), "{stdout}");
+}
+
+#[test]
+fn test_doctor_fix_removes_missing_paths() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    let kept = create_test_file(&ctx.working_path, "kept.txt", "a");
+    let gone = create_test_file(&ctx.working_path, "gone.txt", "b");
+    note_path(&ctx.db_path, None, kept.to_str().unwrap(), 1, &[], &[]);
+    note_path(&ctx.db_path, None, gone.to_str().unwrap(), 1, &[], &[]);
+    std::fs::remove_file(&gone).unwrap();
+    age_path_by(&ctx.db_path, &gone, 31 * 86400);
+
+    let (success, stdout) = doctor(&ctx, &home, &[]);
+    assert!(success, "Warnings alone shouldn't fail:\n{stdout}");
+    assert!(
+        stdout.contains(
+            "[WARN] Missing paths: 1 paths no longer exist and were last noted over 30 days ago"
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains("memy doctor --fix"), "{stdout}");
+
+    let fixed = doctor_fix(&ctx, &home);
+    assert!(fixed.contains("removed 1 missing"), "{fixed}");
+    assert_eq!(
+        stored_paths(&ctx.db_path),
+        vec![(kept.to_str().unwrap().to_owned(), 1)]
+    );
+}
+
+#[test]
+fn test_doctor_fix_keeps_recently_missing_paths() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    let recent = create_test_file(&ctx.working_path, "recent.txt", "a");
+    note_path(&ctx.db_path, None, recent.to_str().unwrap(), 1, &[], &[]);
+    std::fs::remove_file(&recent).unwrap();
+
+    let (_, stdout) = doctor(&ctx, &home, &[]);
+    assert!(
+        stdout.contains("[PASS] Missing paths: 1 paths no longer exist, kept until 30 days"),
+        "{stdout}"
+    );
+    let fixed = doctor_fix(&ctx, &home);
+    assert!(fixed.contains("removed 0 missing"), "{fixed}");
+    assert_eq!(stored_paths(&ctx.db_path).len(), 1);
+
+    // A negative setting means missing paths are never removed
+    age_path_by(&ctx.db_path, &recent, 365 * 86400);
+    let (_, never) = doctor(
+        &ctx,
+        &home,
+        &["--config", "missing_files_delete_from_db_after=-1"],
+    );
+    assert!(
+        never.contains("kept because missing_files_delete_from_db_after is negative"),
+        "{never}"
+    );
+}
+
+#[test]
+fn test_doctor_fix_removes_denied_paths() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    let secret = create_test_file(&ctx.working_path, "secret.txt", "a");
+    note_path(&ctx.db_path, None, secret.to_str().unwrap(), 1, &[], &[]);
+
+    let (_, stdout) = doctor(&ctx, &home, &["--config", "denylist=['*.txt']"]);
+    assert!(
+        stdout.contains("[WARN] Denied paths: 1 paths match the denylist"),
+        "{stdout}"
+    );
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &[
+            "--config",
+            "import_on_first_use=false",
+            "--config",
+            "denylist=['*.txt']",
+            "doctor",
+            "--fix",
+        ],
+        vec![("HOME", home.to_str().unwrap())],
+    );
+    assert!(output.status.success());
+    assert!(stored_paths(&ctx.db_path).is_empty());
+}
+
+#[test]
+fn test_doctor_fix_merges_symlink_duplicates() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    let target = create_test_file(&ctx.working_path, "target.txt", "a");
+    let link = ctx.working_path.join("link.txt");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    note_path(&ctx.db_path, None, target.to_str().unwrap(), 2, &[], &[]);
+    note_path(
+        &ctx.db_path,
+        None,
+        link.to_str().unwrap(),
+        3,
+        &["--config", "normalize_symlinks_on_note=false"],
+        &[],
+    );
+    assert_eq!(stored_paths(&ctx.db_path).len(), 2);
+
+    let (_, stdout) = doctor(&ctx, &home, &[]);
+    assert!(
+        stdout.contains("[WARN] Duplicate paths: 2 entries are symlinks to the same 1 paths"),
+        "{stdout}"
+    );
+
+    let fixed = doctor_fix(&ctx, &home);
+    assert!(fixed.contains("merged 1 duplicates"), "{fixed}");
+    assert_eq!(
+        stored_paths(&ctx.db_path),
+        vec![(target.to_str().unwrap().to_owned(), 5)]
+    );
+}
+
+#[test]
+fn test_doctor_checks_shell_rc_files() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    create_test_file(&home, ".bashrc", "source <(memy hook bash)\n");
+    create_test_file(&home, ".zshrc", "export EDITOR=vim\n");
+
+    let (success, stdout) = doctor(&ctx, &home, &[]);
+    assert!(success);
+    assert!(
+        stdout.contains("[PASS] Shell hooks: bash hook found in"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("[WARN] Shell hooks: zsh hook not found in"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("memy hook zsh"), "{stdout}");
+    assert!(!stdout.contains("fish"), "{stdout}");
+}
+
+#[test]
+fn test_doctor_ignores_other_mentions_of_memy() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    create_test_file(
+        &home,
+        ".bashrc",
+        "# source <(memy hook bash)\nexport PATH=~/src/memy/target/release:$PATH\n",
+    );
+    let fish_hook = String::from_utf8(
+        memy_cmd_test_defaults(&ctx.db_path, None, &["hook", "fish.fish"]).stdout,
+    )
+    .unwrap();
+    std::fs::create_dir_all(home.join(".config/fish")).unwrap();
+    create_test_file(&home, ".config/fish/config.fish", &fish_hook);
+
+    let (success, stdout) = doctor(&ctx, &home, &[]);
+    assert!(success, "{stdout}");
+    assert!(
+        stdout.contains("[WARN] Shell hooks: bash hook not found in"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("[PASS] Shell hooks: fish hook found in"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_doctor_reads_zshrc_from_zdotdir() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+    let (_zdotdir_dir, zdotdir) = temp_dir();
+
+    create_test_file(&home, ".zshrc", "export EDITOR=vim\n");
+    create_test_file(&zdotdir, ".zshrc", "eval \"$(memy hook zsh)\"\n");
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["--config", "import_on_first_use=false", "doctor"],
+        vec![
+            ("HOME", home.to_str().unwrap()),
+            ("ZDOTDIR", zdotdir.to_str().unwrap()),
+        ],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "[PASS] Shell hooks: zsh hook found in {}",
+        zdotdir.join(".zshrc").display()
+    );
+    assert!(stdout.contains(&expected), "{stdout}");
+}
+
+#[test]
+fn test_doctor_reports_config_file() {
+    let ctx = TestContext::new();
+    let (_home_dir, home) = temp_dir();
+
+    create_config_file(&ctx.config_path, "import_on_first_use = false");
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        Some(&ctx.config_path),
+        &["doctor"],
+        vec![("HOME", home.to_str().unwrap())],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "[PASS] Config file: {}",
+        ctx.config_path.join("memy.toml").display()
+    );
+    assert!(stdout.contains(&expected), "{stdout}");
+}
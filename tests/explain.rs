@@ -0,0 +1,147 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+fn explain(ctx: &TestContext, common_args: &[&str], path: &std::path::Path) -> String {
+    let mut args = common_args.to_vec();
+    args.extend(["explain", path.to_str().unwrap()]);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &args);
+    assert!(
+        output.status.success(),
+        "explain failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// `frequent` was noted four times just now and `stale` once, ten days ago.
+fn setup() -> (TestContext, std::path::PathBuf, std::path::PathBuf) {
+    let ctx = TestContext::new();
+
+    let frequent = create_test_file(&ctx.working_path, "frequent.txt", "a");
+    let stale = create_test_file(&ctx.working_path, "stale.txt", "b");
+    note_path(&ctx.db_path, None, frequent.to_str().unwrap(), 4, &[], &[]);
+    note_path(&ctx.db_path, None, stale.to_str().unwrap(), 1, &[], &[]);
+    age_path_by(&ctx.db_path, &stale, 10 * 86400);
+
+    (ctx, frequent, stale)
+}
+
+#[test]
+fn test_explain_shows_row_and_components() {
+    let (ctx, frequent, stale) = setup();
+
+    let top = explain(&ctx, &["--config", "recency_bias=0.25"], &frequent);
+    assert!(top.contains(&format!("Path: {}", frequent.display())));
+    assert!(top.contains("Noted count: 4"));
+    assert!(top.contains("Pinned: no"));
+    assert!(top.contains("Frecency (linear algorithm):"));
+    assert!(top.contains("Frequency: 1.0000 (count 4 / highest count 4)"));
+    assert!(top.contains("Lambda: 0.25 (recency_bias)"));
+    assert!(top.contains("Rank: 1 of 2"));
+
+    let bottom = explain(&ctx, &["--config", "recency_bias=0.25"], &stale);
+    assert!(bottom.contains("Frequency: 0.2500 (count 1 / highest count 4)"));
+    assert!(bottom.contains("Recency: 0.0000"));
+    assert!(bottom.contains("Score: 0.1875"));
+    assert!(bottom.contains("Rank: 2 of 2"));
+}
+
+#[test]
+fn test_explain_rank_puts_pinned_first() {
+    let (ctx, _, stale) = setup();
+
+    let pin = memy_cmd_test_defaults(&ctx.db_path, None, &["pin", stale.to_str().unwrap()]);
+    assert!(pin.status.success());
+
+    let output = explain(&ctx, &[], &stale);
+    assert!(output.contains("Pinned: yes"));
+    assert!(output.contains("Rank: 1 of 2"));
+}
+
+#[test]
+fn test_explain_other_algorithms() {
+    let (ctx, frequent, _) = setup();
+
+    let output = explain(&ctx, &["--config", "frecency_algorithm=zoxide"], &frequent);
+    assert!(output.contains("Frecency (zoxide algorithm):"));
+    assert!(output.contains("not used by the zoxide algorithm"));
+    assert!(output.contains("Score: 1.0000"));
+}
+
+#[test]
+fn test_explain_missing_path() {
+    let (ctx, frequent, stale) = setup();
+
+    std::fs::remove_file(&stale).unwrap();
+
+    let missing = explain(&ctx, &[], &stale);
+    assert!(missing.contains("Missing: yes"));
+    assert!(missing.contains("Rank: not listed, the path is missing"));
+
+    let present = explain(&ctx, &[], &frequent);
+    assert!(present.contains("Missing: no"));
+    assert!(present.contains("Rank: 1 of 1"));
+}
+
+#[test]
+fn test_explain_denylisted_path() {
+    let (ctx, frequent, stale) = setup();
+
+    let denied = explain(&ctx, &["--config", "denylist=[\"stale.*\"]"], &stale);
+    assert!(denied.contains("Denylisted: yes, by 'stale.*' from the denylist config option"));
+    assert!(denied.contains("Rank: not listed, the path is denylisted"));
+
+    let allowed = explain(
+        &ctx,
+        &["--config", "denylist=[\"*.txt\", \"!frequent.txt\"]"],
+        &frequent,
+    );
+    assert!(allowed.contains("Denylisted: no, allowed by '!frequent.txt'"));
+    assert!(allowed.contains("Rank: 1 of 1"));
+}
+
+#[test]
+fn test_explain_denylist_pattern_origin() {
+    let (ctx, _, stale) = setup();
+
+    // The default denylist would refuse to note this, so store it under the stale path's row.
+    let editmsg = create_test_file(&ctx.working_path, "COMMIT_EDITMSG", "c");
+    for table in ["paths", "events"] {
+        execute_sql(
+            &ctx.db_path,
+            &format!(
+                "UPDATE {table} SET path = '{}' WHERE path = '{}'",
+                editmsg.display(),
+                stale.display()
+            ),
+        );
+    }
+
+    let default = explain(&ctx, &[], &editmsg);
+    assert!(
+        default.contains("Denylisted: yes, by 'COMMIT_EDITMSG' from the default denylist"),
+        "Matched by the default denylist: {default}"
+    );
+
+    let repeated = explain(
+        &ctx,
+        &["--config", "denylist=[\"COMMIT_EDITMSG\"]"],
+        &editmsg,
+    );
+    assert!(
+        repeated.contains("Denylisted: yes, by 'COMMIT_EDITMSG' from the denylist config option"),
+        "A config pattern that repeats a default one comes last: {repeated}"
+    );
+}
+
+#[test]
+fn test_explain_unknown_path() {
+    let ctx = TestContext::new();
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["explain", "/does/not/exist"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Not in the database"));
+}
@@ -0,0 +1,238 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+fn export(db_path: &std::path::Path, format: &str) -> String {
+    let output = memy_cmd_test_defaults(db_path, None, &["export", "--format", format]);
+    assert!(output.status.success(), "Export should succeed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn import(db_path: &std::path::Path, file: &std::path::Path, extra_args: &[&str]) -> String {
+    let mut args = vec!["import", "--file", file.to_str().unwrap()];
+    args.extend(extra_args);
+    let output = memy_cmd_test_defaults(db_path, None, &args);
+    assert!(
+        output.status.success(),
+        "Import should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn counts_by_path(db_path: &std::path::Path) -> Vec<(String, u64)> {
+    let exported: Value = serde_json::from_str(&export(db_path, "json")).unwrap();
+    exported["paths"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["path"].as_str().unwrap().to_owned(),
+                row["noted_count"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_export_json_contains_paths_and_state() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 2, &[], &[]);
+
+    let exported: Value = serde_json::from_str(&export(&ctx.db_path, "json")).unwrap();
+
+    assert_eq!(exported["format_version"], 1);
+    assert_eq!(exported["paths"][0]["path"], file_a.to_str().unwrap());
+    assert_eq!(exported["paths"][0]["noted_count"], 2);
+    assert!(exported["state"].is_object(), "State should be exported");
+}
+
+#[test]
+fn test_export_import_round_trip_json() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    let file_b = create_test_file(&ctx.working_path, "file_b.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 2, &[], &[]);
+    note_path(&ctx.db_path, None, file_b.to_str().unwrap(), 1, &[], &[]);
+
+    let export_file = create_test_file(
+        &ctx.working_path,
+        "export.json",
+        &export(&ctx.db_path, "json"),
+    );
+
+    let stdout = import(&other.db_path, &export_file, &[]);
+    assert!(stdout.contains("2 added"), "Unexpected summary: {stdout}");
+    assert_eq!(counts_by_path(&other.db_path), counts_by_path(&ctx.db_path));
+}
+
+#[test]
+fn test_export_import_keeps_tags() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 1, &[], &[]);
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["tag", "add", "docs", file_a.to_str().unwrap()],
+    );
+    assert!(output.status.success());
+
+    let export_file = create_test_file(
+        &ctx.working_path,
+        "export.json",
+        &export(&ctx.db_path, "json"),
+    );
+
+    let dry_run = import(&other.db_path, &export_file, &["--dry-run"]);
+    assert!(
+        dry_run.contains("add tag docs to"),
+        "Unexpected dry run: {dry_run}"
+    );
+
+    import(&other.db_path, &export_file, &[]);
+    let tagged = list_paths(&other.db_path, None, &[], &["--tag", "docs"]);
+    assert_lines_eq(&tagged, &[file_a.to_str().unwrap()]);
+}
+
+#[test]
+fn test_export_import_round_trip_csv() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 3, &[], &[]);
+
+    let csv = export(&ctx.db_path, "csv");
+    assert!(csv.starts_with("path,noted_count,last_noted_timestamp"));
+    let export_file = create_test_file(&ctx.working_path, "export.csv", &csv);
+
+    import(&other.db_path, &export_file, &[]);
+    assert_eq!(counts_by_path(&other.db_path), counts_by_path(&ctx.db_path));
+}
+
+#[test]
+fn test_import_merge_strategies() {
+    for (strategy, expected_count) in [("sum", 5), ("max", 3), ("newest", 2)] {
+        let ctx = TestContext::new();
+
+        let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+        note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 3, &[], &[]);
+
+        let export_contents = format!(
+            r#"{{"format_version": 1, "exported_at": "", "paths": [{{"path": "{}", "noted_count": 2, "last_noted_timestamp": 4102444800}}]}}"#,
+            file_a.to_str().unwrap()
+        );
+        let export_file = create_test_file(&ctx.working_path, "export.json", &export_contents);
+
+        let stdout = import(&ctx.db_path, &export_file, &["--strategy", strategy]);
+        assert!(stdout.contains("1 updated"), "Unexpected summary: {stdout}");
+        assert_eq!(
+            counts_by_path(&ctx.db_path),
+            vec![(file_a.to_str().unwrap().to_owned(), expected_count)],
+            "Unexpected count for strategy {strategy}"
+        );
+    }
+}
+
+#[test]
+fn test_import_records_events_only_for_new_notes() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 3, &[], &[]);
+
+    // An older last note adds to the count but isn't a new note to record
+    let older = format!(
+        r#"{{"format_version": 1, "exported_at": "", "paths": [{{"path": "{}", "noted_count": 2, "last_noted_timestamp": 1000}}]}}"#,
+        file_a.to_str().unwrap()
+    );
+    let older_file = create_test_file(&ctx.working_path, "older.json", &older);
+    import(&ctx.db_path, &older_file, &["--strategy", "sum"]);
+    assert_eq!(count_events(&ctx.db_path, &file_a), 3);
+
+    let newer = older.replace("1000", "4102444800");
+    let newer_file = create_test_file(&ctx.working_path, "newer.json", &newer);
+    import(&ctx.db_path, &newer_file, &["--strategy", "sum"]);
+    assert_eq!(count_events(&ctx.db_path, &file_a), 4);
+}
+
+#[test]
+fn test_import_merges_duplicates_and_drops_stray_tags() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    let path = file_a.to_str().unwrap();
+    let export_contents = format!(
+        r#"{{"format_version": 1, "exported_at": "", "paths": [
+            {{"path": "{path}", "noted_count": 2, "last_noted_timestamp": 1000}},
+            {{"path": "{path}", "noted_count": 3, "last_noted_timestamp": 2000}}
+        ], "tags": {{"{path}": ["work"], "/not/in/export": ["stray"]}}}}"#
+    );
+    let export_file = create_test_file(&ctx.working_path, "export.json", &export_contents);
+
+    let stdout = import(&ctx.db_path, &export_file, &["--strategy", "sum"]);
+    assert!(stdout.contains("1 added"), "Unexpected summary: {stdout}");
+    assert_eq!(counts_by_path(&ctx.db_path), vec![(path.to_owned(), 5)]);
+
+    let conn = rusqlite::Connection::open(ctx.db_path.join("memy.sqlite3")).unwrap();
+    let tagged: Vec<String> = conn
+        .prepare("SELECT path FROM tags")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(tagged, [path]);
+}
+
+#[test]
+fn test_import_dry_run_makes_no_changes() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 1, &[], &[]);
+    let export_file = create_test_file(
+        &ctx.working_path,
+        "export.json",
+        &export(&ctx.db_path, "json"),
+    );
+
+    let stdout = import(&other.db_path, &export_file, &["--dry-run"]);
+    assert!(
+        stdout.contains(&format!("add {}", file_a.to_str().unwrap())),
+        "Dry run should describe the change: {stdout}"
+    );
+    assert!(stdout.contains("Would import: 1 added"));
+    assert!(counts_by_path(&other.db_path).is_empty());
+}
+
+#[test]
+fn test_import_rejects_newer_format_version() {
+    let ctx = TestContext::new();
+
+    let export_file = create_test_file(
+        &ctx.working_path,
+        "export.json",
+        r#"{"format_version": 999, "exported_at": "", "paths": []}"#,
+    );
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["import", "--file", export_file.to_str().unwrap()],
+    );
+    assert!(!output.status.success(), "Import should fail");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("format version mismatch"));
+}
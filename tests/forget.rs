@@ -0,0 +1,186 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+#[test]
+fn test_forget_exact_path() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    let file_b = create_test_file(&ctx.working_path, "file_b.txt", "content");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b]);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["forget", file_a.to_str().unwrap()]);
+    assert!(output.status.success(), "Forget should succeed");
+
+    let lines = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&lines, &[file_b.to_str().unwrap()]);
+}
+
+#[test]
+fn test_forget_path_that_no_longer_exists() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 1, &[], &[]);
+    std::fs::remove_file(&file_a).unwrap();
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["forget", "--dry-run", file_a.to_str().unwrap()],
+    );
+    assert!(output.status.success(), "Forget should succeed");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        file_a.to_str().unwrap()
+    );
+}
+
+#[test]
+fn test_forget_keywords() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "secret_notes.txt", "content");
+    let file_b = create_test_file(&ctx.working_path, "other.txt", "content");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b]);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["forget", "-k", "secret"]);
+    assert!(output.status.success(), "Forget should succeed");
+
+    let lines = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&lines, &[file_b.to_str().unwrap()]);
+}
+
+#[test]
+fn test_forget_glob() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.log", "content");
+    let file_b = create_test_file(&ctx.working_path, "file_b.txt", "content");
+    let dir_c = create_test_directory(&ctx.working_path, "build");
+    let file_c = create_test_file(&dir_c, "file_c.txt", "content");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b, &file_c]);
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["forget", "--glob", "*.log", "--glob", "build/"],
+    );
+    assert!(output.status.success(), "Forget should succeed");
+
+    let lines = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&lines, &[file_b.to_str().unwrap()]);
+}
+
+#[test]
+fn test_forget_dry_run_keeps_paths() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["forget", "--dry-run", "--glob", "*.txt"],
+    );
+    assert!(output.status.success(), "Dry run should succeed");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        file_a.to_str().unwrap()
+    );
+
+    let lines = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&lines, &[file_a.to_str().unwrap()]);
+}
+
+#[test]
+fn test_forget_no_match() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["forget", "-k", "zzznomatch"]);
+    assert!(!output.status.success(), "Forget should fail with no match");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no match found"));
+}
+
+#[test]
+fn test_forget_requires_confirmation_above_threshold() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    let file_b = create_test_file(&ctx.working_path, "file_b.txt", "content");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b]);
+
+    let refused_output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &[
+            "--config",
+            "forget_confirm_threshold=1",
+            "forget",
+            "--glob",
+            "*.txt",
+        ],
+    );
+    assert!(
+        !refused_output.status.success(),
+        "Forget should refuse without confirmation"
+    );
+    assert!(String::from_utf8_lossy(&refused_output.stderr).contains("--yes"));
+
+    let lines_before = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_eq!(lines_before.len(), 2, "Nothing should have been forgotten");
+
+    let confirmed_output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &[
+            "--config",
+            "forget_confirm_threshold=1",
+            "forget",
+            "--yes",
+            "--glob",
+            "*.txt",
+        ],
+    );
+    assert!(
+        confirmed_output.status.success(),
+        "Forget --yes should succeed"
+    );
+
+    let lines = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&lines, &[]);
+}
+
+#[test]
+fn test_forget_dry_run_no_match() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "file_a.txt", "content");
+    note_path(&ctx.db_path, None, file_a.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["forget", "--dry-run", "-k", "zzznomatch"],
+    );
+    assert!(
+        output.status.success(),
+        "A dry run with no match should succeed"
+    );
+    assert!(
+        output.stdout.is_empty(),
+        "No paths to list: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Would forget 0 paths"),
+        "Reports the count: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
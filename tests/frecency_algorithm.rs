@@ -0,0 +1,72 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+/// Notes `dir_a` three times two weeks ago and `dir_b` once just now.
+fn setup_old_frequent_and_new_rare(ctx: &TestContext) {
+    let dir_a = create_test_directory(&ctx.working_path, "dir_a");
+    let dir_b = create_test_directory(&ctx.working_path, "dir_b");
+
+    note_path(&ctx.db_path, None, dir_a.to_str().unwrap(), 3, &[], &[]);
+    age_path_by(&ctx.db_path, &dir_a, 14 * 24 * 60 * 60);
+    note_path(&ctx.db_path, None, dir_b.to_str().unwrap(), 1, &[], &[]);
+}
+
+#[test]
+fn test_frecency_algorithm_linear_pure_frequency() {
+    let ctx = TestContext::new();
+    setup_old_frequent_and_new_rare(&ctx);
+
+    let lines = list_paths(
+        &ctx.db_path,
+        None,
+        &[
+            "--config",
+            "frecency_algorithm=linear",
+            "--config",
+            "recency_bias=0",
+        ],
+        &[],
+    );
+    assert_path_before(&lines, "dir_a", "dir_b");
+}
+
+#[test]
+fn test_frecency_algorithms_favour_recent_notes() {
+    for algorithm in ["zoxide", "fasd", "half-life"] {
+        let ctx = TestContext::new();
+        setup_old_frequent_and_new_rare(&ctx);
+
+        let lines = list_paths(
+            &ctx.db_path,
+            None,
+            &["--config", &format!("frecency_algorithm={algorithm}")],
+            &[],
+        );
+        assert_path_before(&lines, "dir_b", "dir_a");
+    }
+}
+
+#[test]
+fn test_frecency_algorithm_invalid() {
+    let ctx = TestContext::new();
+
+    create_config_file(&ctx.config_path, "frecency_algorithm = \"random\"\n");
+
+    let output = memy_cmd(None, Some(&ctx.config_path), &["list"], vec![]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_frecency_half_life_days_must_be_positive() {
+    let ctx = TestContext::new();
+
+    create_config_file(&ctx.config_path, "frecency_half_life_days = 0.0\n");
+
+    let output = memy_cmd(None, Some(&ctx.config_path), &["list"], vec![]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("must be greater than 0"));
+}
@@ -0,0 +1,94 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+#[test]
+fn test_fuzzy_match_finds_abbreviation() {
+    let ctx = TestContext::new();
+
+    let memy_dir = create_test_directory(&ctx.working_path, "memy");
+    let config_dir = create_test_directory(&memy_dir, "config");
+    let other_dir = create_test_directory(&ctx.working_path, "other");
+    note_path(
+        &ctx.db_path,
+        None,
+        config_dir.to_str().unwrap(),
+        1,
+        &[],
+        &[],
+    );
+    note_path(&ctx.db_path, None, other_dir.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--", "mmycfg"]);
+    assert!(
+        !output.status.success(),
+        "zoxide matching should still be the default"
+    );
+
+    let lines = list_paths(
+        &ctx.db_path,
+        None,
+        &[],
+        &["--match", "fuzzy", "--", "mmycfg"],
+    );
+    assert_lines_eq(&lines, &[config_dir.to_str().unwrap()]);
+}
+
+#[test]
+fn test_fuzzy_match_from_config() {
+    let ctx = TestContext::new();
+
+    let dir = create_test_directory(&ctx.working_path, "projects");
+    note_path(&ctx.db_path, None, dir.to_str().unwrap(), 1, &[], &[]);
+
+    let lines_from_config = list_paths(
+        &ctx.db_path,
+        None,
+        &["--config", "keyword_match=\"fuzzy\""],
+        &["--", "prjs"],
+    );
+    assert_lines_eq(&lines_from_config, &[dir.to_str().unwrap()]);
+
+    let lines_overridden = list_paths(
+        &ctx.db_path,
+        None,
+        &["--config", "keyword_match=\"fuzzy\""],
+        &["--match", "zoxide", "--", "proj"],
+    );
+    assert_lines_eq(&lines_overridden, &[dir.to_str().unwrap()]);
+}
+
+#[test]
+fn test_fuzzy_match_score_combines_with_frecency() {
+    let ctx = TestContext::new();
+
+    let close = create_test_directory(&ctx.working_path, "report");
+    let loose = create_test_directory(&ctx.working_path, "xrxexpxoxrxt");
+    note_path(&ctx.db_path, None, close.to_str().unwrap(), 2, &[], &[]);
+    note_path(&ctx.db_path, None, loose.to_str().unwrap(), 2, &[], &[]);
+
+    let lines_equal_counts = list_paths(
+        &ctx.db_path,
+        None,
+        &["--config", "recency_bias=0"],
+        &["--match", "fuzzy", "--", "report"],
+    );
+    assert_lines_eq(
+        &lines_equal_counts,
+        &[close.to_str().unwrap(), loose.to_str().unwrap()],
+    );
+
+    // Noted often enough, the looser match wins
+    note_path(&ctx.db_path, None, loose.to_str().unwrap(), 10, &[], &[]);
+    let lines_noted_more = list_paths(
+        &ctx.db_path,
+        None,
+        &["--config", "recency_bias=0"],
+        &["--match", "fuzzy", "--", "report"],
+    );
+    assert_lines_eq(
+        &lines_noted_more,
+        &[loose.to_str().unwrap(), close.to_str().unwrap()],
+    );
+}
@@ -190,6 +190,14 @@ fn test_bash_hook_notes_file() {
 
     let paths = list_paths(&ctx.db_path, Some(&ctx.config_path), &[], &[]);
     assert_lines_eq(&paths, &[test_file.to_string_lossy().as_ref()]);
+
+    let bash_paths = list_paths(
+        &ctx.db_path,
+        Some(&ctx.config_path),
+        &[],
+        &["--source", "bash"],
+    );
+    assert_lines_eq(&bash_paths, &[test_file.to_string_lossy().as_ref()]);
 }
 
 #[test]
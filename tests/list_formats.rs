@@ -0,0 +1,136 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+#![allow(
+    clippy::literal_string_with_formatting_args,
+    reason = "Templates use the same brace syntax"
+)]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+fn list_stdout(ctx: &TestContext, list_args: &[&str]) -> String {
+    let mut args = vec!["list"];
+    args.extend(list_args);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &args);
+    assert!(
+        output.status.success(),
+        "list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_list_jsonl() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "a.txt", "a");
+    let file_b = create_test_file(&ctx.working_path, "b.txt", "b");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b]);
+
+    let stdout = list_stdout(&ctx, &["--format", "jsonl"]);
+    let records: Vec<Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["path"], file_b.to_str().unwrap());
+    assert_eq!(records[1]["path"], file_a.to_str().unwrap());
+    assert_eq!(records[0]["count"], 1);
+}
+
+#[test]
+fn test_list_tsv() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "a.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 2, &[], &[]);
+
+    let stdout = list_stdout(&ctx, &["--format", "tsv"]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines[0],
+        "path\tfrecency\tcount\tlast_noted\tfile_type\tpinned\ttags"
+    );
+    let fields: Vec<&str> = lines[1].split('\t').collect();
+    assert_eq!(fields[0], file.to_str().unwrap());
+    assert_eq!(fields[2], "2");
+    assert_eq!(fields[4], "file");
+}
+
+#[test]
+fn test_list_null_separated() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "line\nbreak.txt", "a");
+    let other = create_test_file(&ctx.working_path, "other.txt", "a");
+    note_paths_with_delay(&ctx.db_path, None, &[&file, &other]);
+
+    let stdout = list_stdout(&ctx, &["-0"]);
+    let expected = format!("{}\0{}\0", other.to_str().unwrap(), file.to_str().unwrap());
+    assert_eq!(stdout, expected);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "-0", "--format", "json"]);
+    assert!(!output.status.success(), "-0 makes no sense with JSON");
+}
+
+#[test]
+fn test_list_template() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "a.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 3, &[], &[]);
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["tag", "add", "docs", file.to_str().unwrap()],
+    );
+    assert!(output.status.success());
+
+    let stdout = list_stdout(
+        &ctx,
+        &[
+            "--template",
+            r"{path}\t{count}\t{last_noted:relative}\t{file_type}\t{tags}\t{frecency:.2}",
+        ],
+    );
+    // The only path has the highest count, but no recency compared to itself
+    assert_eq!(
+        stdout,
+        format!(
+            "{}\t3\tjust now\tfile\tdocs\t0.50\n",
+            file.to_str().unwrap()
+        )
+    );
+}
+
+#[test]
+fn test_list_template_null_separated() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "a.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+
+    let stdout = list_stdout(&ctx, &["-0", "--template", "{count} {path}"]);
+    assert_eq!(stdout, format!("1 {}\0", file.to_str().unwrap()));
+}
+
+#[test]
+fn test_list_template_errors() {
+    let ctx = TestContext::new();
+
+    for (template, message) in [
+        ("{nonsense}", "Unknown template field 'nonsense'"),
+        ("{count:relative}", "Unknown template modifier 'relative'"),
+        ("{frecency:.x}", "Unknown template modifier '.x'"),
+        ("{path", "Unclosed"),
+    ] {
+        let output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--template", template]);
+        assert!(!output.status.success(), "{template} should fail");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(message), "{template}: {stderr}");
+    }
+}
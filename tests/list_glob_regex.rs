@@ -0,0 +1,92 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+struct Tree {
+    ctx: TestContext,
+    main_tf: String,
+    vars_tf: String,
+    readme: String,
+    other_tf: String,
+}
+
+fn create_tree() -> Tree {
+    let ctx = TestContext::new();
+
+    let infra = create_test_directory(&ctx.working_path, "infra");
+    let modules = create_test_directory(&infra, "modules");
+    let main_tf = create_test_file(&infra, "main.tf", "a");
+    let vars_tf = create_test_file(&modules, "vars.tf", "a");
+    let readme = create_test_file(&infra, "README.md", "a");
+    let other_tf = create_test_file(&ctx.working_path, "other.tf", "a");
+
+    for file in [&main_tf, &vars_tf, &readme, &other_tf] {
+        note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+    }
+
+    Tree {
+        main_tf: main_tf.to_str().unwrap().to_owned(),
+        vars_tf: vars_tf.to_str().unwrap().to_owned(),
+        readme: readme.to_str().unwrap().to_owned(),
+        other_tf: other_tf.to_str().unwrap().to_owned(),
+        ctx,
+    }
+}
+
+fn list_sorted(tree: &Tree, list_args: &[&str]) -> Vec<String> {
+    let mut lines = list_paths(&tree.ctx.db_path, None, &[], list_args);
+    lines.sort_unstable();
+    lines
+}
+
+#[test]
+fn test_list_glob_under_directory() {
+    let tree = create_tree();
+    let pattern = format!("{}/infra/**/*.tf", tree.ctx.working_path.display());
+
+    let lines = list_sorted(&tree, &["--glob", &pattern]);
+    assert_lines_eq(&lines, &[&tree.main_tf, &tree.vars_tf]);
+}
+
+#[test]
+fn test_list_glob_without_slash_matches_anywhere() {
+    let tree = create_tree();
+
+    let lines = list_sorted(&tree, &["--glob", "*.tf"]);
+    assert_lines_eq(&lines, &[&tree.main_tf, &tree.vars_tf, &tree.other_tf]);
+}
+
+#[test]
+fn test_list_repeated_globs_match_any() {
+    let tree = create_tree();
+
+    let lines = list_sorted(&tree, &["--glob", "main.tf", "--glob", "*.md"]);
+    assert_lines_eq(&lines, &[&tree.readme, &tree.main_tf]);
+}
+
+#[test]
+fn test_list_exclude_glob() {
+    let tree = create_tree();
+    let modules = format!("{}/infra/modules/", tree.ctx.working_path.display());
+
+    let lines = list_sorted(&tree, &["--glob", "*.tf", "--exclude-glob", &modules]);
+    assert_lines_eq(&lines, &[&tree.main_tf, &tree.other_tf]);
+}
+
+#[test]
+fn test_list_regex() {
+    let tree = create_tree();
+
+    let lines = list_sorted(&tree, &["--regex", r"/infra/[^/]+\.(tf|md)$"]);
+    assert_lines_eq(&lines, &[&tree.readme, &tree.main_tf]);
+}
+
+#[test]
+fn test_list_invalid_regex_fails() {
+    let tree = create_tree();
+
+    let output = memy_cmd_test_defaults(&tree.ctx.db_path, None, &["list", "--regex", "(tf"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid regex"));
+}
@@ -0,0 +1,108 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+struct KindFixture {
+    ctx: TestContext,
+    notes: std::path::PathBuf,
+    todo: std::path::PathBuf,
+    photo: std::path::PathBuf,
+    script: std::path::PathBuf,
+    unnamed_png: std::path::PathBuf,
+}
+
+fn setup() -> KindFixture {
+    let ctx = TestContext::new();
+
+    let notes = create_test_file(&ctx.working_path, "notes.md", "# Notes");
+    let todo = create_test_file(&ctx.working_path, "TODO.TXT", "nothing");
+    let photo = create_test_file(&ctx.working_path, "photo.jpg", "not really a jpeg");
+    let script = create_test_file(&ctx.working_path, "build.rs", "fn main() {}");
+    let unnamed_png = ctx.working_path.join("download");
+    std::fs::write(&unnamed_png, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+    let docs_dir = create_test_directory(&ctx.working_path, "docs.md");
+
+    for path in [&notes, &todo, &photo, &script, &unnamed_png, &docs_dir] {
+        note_path(&ctx.db_path, None, path.to_str().unwrap(), 1, &[], &[]);
+    }
+
+    KindFixture {
+        ctx,
+        notes,
+        todo,
+        photo,
+        script,
+        unnamed_png,
+    }
+}
+
+fn list(fixture: &KindFixture, args: &[&str]) -> Vec<String> {
+    let mut lines = list_paths(&fixture.ctx.db_path, None, &[], args);
+    lines.sort();
+    lines
+}
+
+fn paths(expected: &[&std::path::PathBuf]) -> Vec<String> {
+    let mut lines: Vec<String> = expected
+        .iter()
+        .map(|path| path.to_str().unwrap().to_owned())
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn test_ext_filter() {
+    let fixture = setup();
+
+    // Directories never match, even if their name looks like it has the extension
+    assert_eq!(
+        list(&fixture, &["--ext", "md,txt"]),
+        paths(&[&fixture.notes, &fixture.todo])
+    );
+    assert_eq!(
+        list(&fixture, &["--ext", ".md", "--ext", "rs"]),
+        paths(&[&fixture.notes, &fixture.script])
+    );
+}
+
+#[test]
+fn test_kind_filter() {
+    let fixture = setup();
+
+    assert_eq!(
+        list(&fixture, &["--kind", "document"]),
+        paths(&[&fixture.notes, &fixture.todo])
+    );
+    assert_eq!(
+        list(&fixture, &["--kind", "image", "--kind", "source"]),
+        paths(&[&fixture.photo, &fixture.script])
+    );
+}
+
+#[test]
+fn test_kind_filter_with_sniffing() {
+    let fixture = setup();
+
+    // The extension wins over the contents where there is one
+    assert_eq!(
+        list(&fixture, &["--kind", "image", "--sniff"]),
+        paths(&[&fixture.photo, &fixture.unnamed_png])
+    );
+}
+
+#[test]
+fn test_kind_rejects_unknown_kinds() {
+    let fixture = setup();
+
+    let unknown_kind = memy_cmd_test_defaults(
+        &fixture.ctx.db_path,
+        None,
+        &["list", "--kind", "spreadsheet"],
+    );
+    assert!(!unknown_kind.status.success());
+
+    let sniff_alone = memy_cmd_test_defaults(&fixture.ctx.db_path, None, &["list", "--sniff"]);
+    assert!(!sniff_alone.status.success(), "--sniff needs --kind");
+}
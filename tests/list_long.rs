@@ -0,0 +1,103 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+fn list_long(ctx: &TestContext, extra_args: &[&str]) -> Vec<String> {
+    let mut args = vec!["list", "--long"];
+    args.extend(extra_args);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &args);
+    assert!(
+        output.status.success(),
+        "list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn test_list_long_columns() {
+    let ctx = TestContext::new();
+
+    let dir = create_test_directory(&ctx.working_path, "project");
+    let file = create_test_file(&ctx.working_path, "notes.txt", "a");
+    note_path(&ctx.db_path, None, dir.to_str().unwrap(), 1, &[], &[]);
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 12, &[], &[]);
+    age_path_by(&ctx.db_path, &dir, 3 * 86400);
+
+    let lines = list_long(&ctx, &[]);
+    assert_eq!(lines.len(), 3);
+
+    let header: Vec<&str> = lines[0].split("  ").map(str::trim).collect();
+    assert!(header.starts_with(&["FRECENCY", "COUNT", "LAST NOTED", "TYPE"]));
+    assert!(lines[0].ends_with("PATH"));
+
+    let file_fields: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(file_fields[1], "12");
+    assert_eq!(file_fields[2..5], ["just", "now", "file"]);
+    assert_eq!(file_fields[5], file.to_str().unwrap());
+
+    let dir_fields: Vec<&str> = lines[2].split_whitespace().collect();
+    assert_eq!(dir_fields[1], "1");
+    assert_eq!(dir_fields[2..5], ["3d", "ago", "dir"]);
+    assert_eq!(dir_fields[5], dir.to_str().unwrap());
+
+    // Paths start in the same column
+    let path_column = lines[1].find(file_fields[5]).unwrap();
+    assert_eq!(lines[2].find(dir_fields[5]).unwrap(), path_column);
+    assert_eq!(lines[0].find("PATH").unwrap(), path_column);
+}
+
+#[test]
+fn test_list_long_pretty_paths() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "notes.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &[
+            "--config",
+            "import_on_first_use=false",
+            "list",
+            "--long",
+            "--pretty-paths",
+        ],
+        vec![("HOME", ctx.working_path.to_str().unwrap())],
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.lines().nth(1).unwrap().ends_with("  ~/notes.txt"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_list_long_conflicts_with_machine_formats_and_filters() {
+    let ctx = TestContext::new();
+
+    for args in [
+        &["list", "--long", "--format", "json"][..],
+        &["list", "--long", "-0"],
+        &["list", "--long", "--template", "{path}"],
+        &["list", "--long", "--output-filter"],
+        &[
+            "list",
+            "--long",
+            "--output-filter",
+            "--output-filter-command",
+            "cat",
+        ],
+    ] {
+        let output = memy_cmd_test_defaults(&ctx.db_path, None, args);
+        assert!(!output.status.success(), "{args:?} should fail");
+    }
+}
@@ -290,3 +290,76 @@ fn test_output_filter_with_malformed_command() {
         "Error message should mention shell command failure, got: {stderr}"
     );
 }
+
+/// Runs `memy list --output-filter` with the built-in picker, typing `keys`, and returns what it
+/// printed after leaving the picker's screen.
+fn pick_with_builtin_picker(ctx: &TestContext, keys: &[&str]) -> String {
+    let output = memy_cmd_terminal_with_keys(
+        Some(&ctx.db_path),
+        None,
+        &[
+            "--config",
+            "import_on_first_use=false",
+            "list",
+            "--output-filter",
+        ],
+        vec![("MEMY_OUTPUT_FILTER", "builtin")],
+        keys,
+    );
+    assert!(output.status.success(), "Command should succeed");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (_, after_picker) = stdout
+        .rsplit_once("\x1b[?1049l")
+        .expect("Picker should leave the alternate screen");
+    after_picker.replace('\r', "")
+}
+
+#[test]
+fn test_builtin_picker_filters_and_selects() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "alpha.txt", "content a");
+    let file_b = create_test_file(&ctx.working_path, "beta.txt", "content b");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b]);
+
+    let picked = pick_with_builtin_picker(&ctx, &["alp", "\r"]);
+    assert_eq!(picked, format!("{}\n", file_a.to_str().unwrap()));
+}
+
+#[test]
+fn test_builtin_picker_multi_select() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "alpha.txt", "content a");
+    let file_b = create_test_file(&ctx.working_path, "beta.txt", "content b");
+    let file_c = create_test_file(&ctx.working_path, "gamma.txt", "content c");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b, &file_c]);
+
+    // Most frecent first: gamma, beta, alpha. Select gamma and alpha.
+    let picked = pick_with_builtin_picker(&ctx, &["\t", "\x1b[B", "\t", "\r"]);
+    assert_eq!(
+        picked,
+        format!(
+            "{}\n{}\n",
+            file_c.to_str().unwrap(),
+            file_a.to_str().unwrap()
+        )
+    );
+}
+
+#[test]
+fn test_builtin_picker_forget_and_cancel() {
+    let ctx = TestContext::new();
+
+    let file_a = create_test_file(&ctx.working_path, "alpha.txt", "content a");
+    let file_b = create_test_file(&ctx.working_path, "beta.txt", "content b");
+    note_paths_with_delay(&ctx.db_path, None, &[&file_a, &file_b]);
+
+    // Ctrl-X forgets beta, then Ctrl-C cancels without picking anything
+    let picked = pick_with_builtin_picker(&ctx, &["\x18", "\x03"]);
+    assert_eq!(picked, "");
+
+    let remaining = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_lines_eq(&remaining, &[file_a.to_str().unwrap()]);
+}
@@ -0,0 +1,158 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+struct ThresholdFixture {
+    ctx: TestContext,
+    stale: std::path::PathBuf,
+    recent_rare: std::path::PathBuf,
+    recent_frequent: std::path::PathBuf,
+}
+
+/// `stale` was noted once 200 days ago, `recent_rare` once 3 days ago and `recent_frequent` five
+/// times just now.
+fn setup() -> ThresholdFixture {
+    let ctx = TestContext::new();
+
+    let stale = create_test_file(&ctx.working_path, "stale.txt", "a");
+    let recent_rare = create_test_file(&ctx.working_path, "recent_rare.txt", "b");
+    let recent_frequent = create_test_file(&ctx.working_path, "recent_frequent.txt", "c");
+
+    note_path(&ctx.db_path, None, stale.to_str().unwrap(), 1, &[], &[]);
+    note_path(
+        &ctx.db_path,
+        None,
+        recent_rare.to_str().unwrap(),
+        1,
+        &[],
+        &[],
+    );
+    note_path(
+        &ctx.db_path,
+        None,
+        recent_frequent.to_str().unwrap(),
+        5,
+        &[],
+        &[],
+    );
+    age_path_by(&ctx.db_path, &stale, 200 * 86400);
+    age_path_by(&ctx.db_path, &recent_rare, 3 * 86400);
+
+    ThresholdFixture {
+        ctx,
+        stale,
+        recent_rare,
+        recent_frequent,
+    }
+}
+
+fn list(fixture: &ThresholdFixture, args: &[&str]) -> Vec<String> {
+    let mut lines = list_paths(&fixture.ctx.db_path, None, &[], args);
+    lines.sort();
+    lines
+}
+
+fn paths(expected: &[&std::path::PathBuf]) -> Vec<String> {
+    let mut lines: Vec<String> = expected
+        .iter()
+        .map(|path| path.to_str().unwrap().to_owned())
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn test_older_than() {
+    let fixture = setup();
+
+    assert_eq!(
+        list(&fixture, &["--older-than", "1d"]),
+        paths(&[&fixture.stale, &fixture.recent_rare])
+    );
+    assert_eq!(
+        list(&fixture, &["--older-than", "180d"]),
+        paths(&[&fixture.stale])
+    );
+}
+
+#[test]
+fn test_between() {
+    let fixture = setup();
+
+    assert_eq!(
+        list(&fixture, &["--between", "7d..1d"]),
+        paths(&[&fixture.recent_rare])
+    );
+    assert_eq!(
+        list(&fixture, &["--between", "..7d"]),
+        paths(&[&fixture.stale])
+    );
+
+    let output = memy_cmd_test_defaults(&fixture.ctx.db_path, None, &["list", "--between", "7d"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("A..B"));
+}
+
+#[test]
+fn test_time_filters_combine() {
+    let fixture = setup();
+
+    assert_eq!(
+        list(&fixture, &["--newer-than", "30d", "--older-than", "1d"]),
+        paths(&[&fixture.recent_rare])
+    );
+}
+
+#[test]
+fn test_count_thresholds() {
+    let fixture = setup();
+
+    assert_eq!(
+        list(&fixture, &["--min-count", "2"]),
+        paths(&[&fixture.recent_frequent])
+    );
+    assert_eq!(
+        list(&fixture, &["--max-count", "1"]),
+        paths(&[&fixture.stale, &fixture.recent_rare])
+    );
+
+    // Noted once, more than 6 months ago
+    assert_eq!(
+        list(&fixture, &["--max-count", "1", "--older-than", "180d"]),
+        paths(&[&fixture.stale])
+    );
+}
+
+#[test]
+fn test_min_score() {
+    let fixture = setup();
+
+    // With the default linear algorithm, the most frequent and most recent path scores 1.0 and
+    // the least frequent and least recent one 0.0
+    assert_eq!(
+        list(&fixture, &["--min-score", "0.9"]),
+        paths(&[&fixture.recent_frequent])
+    );
+    assert_eq!(list(&fixture, &["--min-score", "0"]).len(), 3);
+}
+
+#[test]
+fn test_count_thresholds_exclude_derived_dirs() {
+    let ctx = TestContext::new();
+
+    let dir = create_test_directory(&ctx.working_path, "project");
+    let file = create_test_file(&dir, "notes.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 2, &[], &[]);
+
+    let derived = list_paths(&ctx.db_path, None, &[], &["--derive-dirs", "-d"]);
+    assert!(derived.contains(&dir.to_str().unwrap().to_owned()));
+
+    let noted = list_paths(
+        &ctx.db_path,
+        None,
+        &[],
+        &["--derive-dirs", "-d", "--min-count", "1"],
+    );
+    assert!(noted.is_empty(), "{noted:?}");
+}
@@ -0,0 +1,143 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+fn list_in_dir(
+    ctx: &TestContext,
+    current_dir: &std::path::Path,
+    list_args: &[&str],
+) -> Vec<String> {
+    let mut args = vec!["--config", "import_on_first_use=false", "list"];
+    args.extend(list_args);
+
+    let output = memy_cmd_in_dir(Some(&ctx.db_path), None, current_dir, &args);
+    assert!(
+        output.status.success(),
+        "list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_owned)
+        .collect();
+    lines.sort_unstable();
+    lines
+}
+
+#[test]
+fn test_list_under_directory() {
+    let ctx = TestContext::new();
+
+    let project = create_test_directory(&ctx.working_path, "project");
+    let src = create_test_directory(&project, "src");
+    let inside = create_test_file(&src, "main.rs", "a");
+    let outside = create_test_file(&ctx.working_path, "other.txt", "a");
+    let sibling = create_test_directory(&ctx.working_path, "project-old");
+
+    for path in [&project, &src, &inside, &outside, &sibling] {
+        note_path(&ctx.db_path, None, path.to_str().unwrap(), 1, &[], &[]);
+    }
+
+    let lines = list_in_dir(
+        &ctx,
+        &ctx.empty_path,
+        &["--under", project.to_str().unwrap()],
+    );
+    assert_lines_eq(&lines, &[src.to_str().unwrap(), inside.to_str().unwrap()]);
+}
+
+#[test]
+fn test_list_under_relative() {
+    let ctx = TestContext::new();
+
+    let project = create_test_directory(&ctx.working_path, "project");
+    let src = create_test_directory(&project, "src");
+    let inside = create_test_file(&src, "main.rs", "a");
+    note_path(&ctx.db_path, None, inside.to_str().unwrap(), 1, &[], &[]);
+
+    let lines = list_in_dir(
+        &ctx,
+        &ctx.working_path,
+        &["--under", "project", "--relative"],
+    );
+    assert_lines_eq(&lines, &["src/main.rs"]);
+}
+
+#[test]
+fn test_list_relative_output_filter_gets_full_paths() {
+    let ctx = TestContext::new();
+
+    let project = create_test_directory(&ctx.working_path, "project");
+    let inside = create_test_file(&project, "main.rs", "a");
+    note_path(&ctx.db_path, None, inside.to_str().unwrap(), 1, &[], &[]);
+
+    let lines = list_in_dir(
+        &ctx,
+        &ctx.working_path,
+        &[
+            "--under",
+            "project",
+            "--relative",
+            "--output-filter",
+            "--output-filter-command",
+            "cat",
+        ],
+    );
+    assert_lines_eq(&lines, &[inside.to_str().unwrap()]);
+}
+
+#[test]
+fn test_list_under_missing_directory_fails() {
+    let ctx = TestContext::new();
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["list", "--under", "/this/path/does/not/exist"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not a directory"));
+}
+
+#[test]
+fn test_list_repo() {
+    let ctx = TestContext::new();
+
+    let repo = create_test_directory(&ctx.working_path, "repo");
+    create_test_directory(&repo, ".git");
+    let src = create_test_directory(&repo, "src");
+    let inside = create_test_file(&src, "lib.rs", "a");
+    let outside = create_test_file(&ctx.working_path, "notes.txt", "a");
+    note_path(&ctx.db_path, None, inside.to_str().unwrap(), 1, &[], &[]);
+    note_path(&ctx.db_path, None, outside.to_str().unwrap(), 1, &[], &[]);
+
+    let lines = list_in_dir(&ctx, &src, &["--repo"]);
+    assert_lines_eq(&lines, &[inside.to_str().unwrap()]);
+
+    let relative_lines = list_in_dir(&ctx, &src, &["--repo", "--relative", "-f"]);
+    assert_lines_eq(&relative_lines, &["src/lib.rs"]);
+}
+
+#[test]
+fn test_list_repo_outside_repository_fails() {
+    let ctx = TestContext::new();
+
+    let output = memy_cmd_in_dir(
+        Some(&ctx.db_path),
+        None,
+        &ctx.empty_path,
+        &["--config", "import_on_first_use=false", "list", "--repo"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not inside a git work tree"));
+}
+
+#[test]
+fn test_list_relative_requires_root() {
+    let ctx = TestContext::new();
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--relative"]);
+    assert!(!output.status.success());
+}
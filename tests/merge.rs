@@ -0,0 +1,284 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use rusqlite::Connection;
+
+fn merge(ctx: &TestContext, other: &TestContext, extra_args: &[&str]) -> std::process::Output {
+    let other_file = other.db_path.join("memy.sqlite3");
+    let mut args = vec!["merge", other_file.to_str().unwrap()];
+    args.extend(extra_args);
+    memy_cmd_test_defaults(&ctx.db_path, None, &args)
+}
+
+fn noted_count(db_path: &std::path::Path, path: &std::path::Path) -> Option<u64> {
+    let conn = Connection::open(db_path.join("memy.sqlite3")).unwrap();
+    conn.query_row(
+        "SELECT noted_count FROM paths WHERE path = ?1",
+        [path.to_str().unwrap()],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+#[test]
+fn test_merge_adds_updates_and_skips_missing() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let shared = create_test_file(&ctx.working_path, "shared.txt", "a");
+    let other_only = create_test_file(&ctx.working_path, "other_only.txt", "b");
+    let gone = create_test_file(&ctx.working_path, "gone.txt", "c");
+
+    note_path(&ctx.db_path, None, shared.to_str().unwrap(), 2, &[], &[]);
+    note_path(&other.db_path, None, shared.to_str().unwrap(), 3, &[], &[]);
+    note_path(
+        &other.db_path,
+        None,
+        other_only.to_str().unwrap(),
+        1,
+        &[],
+        &[],
+    );
+    note_path(&other.db_path, None, gone.to_str().unwrap(), 1, &[], &[]);
+    std::fs::remove_file(&gone).unwrap();
+
+    let output = merge(&ctx, &other, &[]);
+    assert!(
+        output.status.success(),
+        "Merge failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("1 added, 1 updated, 0 unchanged, 1 skipped (missing locally)"),
+        "Unexpected summary: {stdout}"
+    );
+
+    assert_eq!(noted_count(&ctx.db_path, &shared), Some(5));
+    assert_eq!(noted_count(&ctx.db_path, &other_only), Some(1));
+    assert_eq!(noted_count(&ctx.db_path, &gone), None);
+
+    // The other database is left alone
+    assert_eq!(noted_count(&other.db_path, &shared), Some(3));
+}
+
+#[test]
+fn test_merge_copies_events() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let shared = create_test_file(&ctx.working_path, "shared.txt", "a");
+    let other_only = create_test_file(&ctx.working_path, "other_only.txt", "b");
+
+    note_path(&ctx.db_path, None, shared.to_str().unwrap(), 2, &[], &[]);
+    note_path(
+        &other.db_path,
+        None,
+        shared.to_str().unwrap(),
+        3,
+        &[],
+        &["--source", "vim"],
+    );
+    note_path(
+        &other.db_path,
+        None,
+        other_only.to_str().unwrap(),
+        2,
+        &[],
+        &[],
+    );
+
+    assert!(merge(&ctx, &other, &[]).status.success());
+    assert_eq!(count_events(&ctx.db_path, &shared), 5);
+    assert_eq!(count_events(&ctx.db_path, &other_only), 2);
+    assert_eq!(
+        list_paths(&ctx.db_path, None, &[], &["--source", "vim"]),
+        [shared.to_str().unwrap()]
+    );
+
+    // Merging the same database again doesn't duplicate its events
+    assert!(merge(&ctx, &other, &["--strategy", "max"]).status.success());
+    assert_eq!(count_events(&ctx.db_path, &shared), 5);
+}
+
+#[test]
+fn test_merge_max_and_newest_keep_events_matching_counts() {
+    for strategy in ["max", "newest"] {
+        let ctx = TestContext::new();
+        let other = TestContext::new();
+
+        let incoming_wins = create_test_file(&ctx.working_path, "incoming_wins.txt", "a");
+        let local_wins = create_test_file(&ctx.working_path, "local_wins.txt", "b");
+        note_path(
+            &ctx.db_path,
+            None,
+            incoming_wins.to_str().unwrap(),
+            2,
+            &[],
+            &[],
+        );
+        note_path(
+            &ctx.db_path,
+            None,
+            local_wins.to_str().unwrap(),
+            3,
+            &[],
+            &[],
+        );
+        age_path_events_by(&ctx.db_path, &local_wins, 60);
+        note_path(
+            &other.db_path,
+            None,
+            incoming_wins.to_str().unwrap(),
+            3,
+            &[],
+            &[],
+        );
+        note_path(
+            &other.db_path,
+            None,
+            local_wins.to_str().unwrap(),
+            1,
+            &[],
+            &[],
+        );
+        age_path_events_by(&other.db_path, &local_wins, 120);
+
+        assert!(
+            merge(&ctx, &other, &["--strategy", strategy])
+                .status
+                .success()
+        );
+        for path in [&incoming_wins, &local_wins] {
+            assert_eq!(
+                u64::try_from(count_events(&ctx.db_path, path)).ok(),
+                noted_count(&ctx.db_path, path),
+                "{strategy}: events and noted count disagree for {}",
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_merge_strategies() {
+    for (strategy, expected_count) in [("sum", 5), ("max", 3), ("newest", 3)] {
+        let ctx = TestContext::new();
+        let other = TestContext::new();
+
+        let shared = create_test_file(&ctx.working_path, "shared.txt", "a");
+        note_path(&ctx.db_path, None, shared.to_str().unwrap(), 2, &[], &[]);
+        note_path(&other.db_path, None, shared.to_str().unwrap(), 3, &[], &[]);
+        age_path_by(&ctx.db_path, &shared, 60);
+
+        let output = merge(&ctx, &other, &["--strategy", strategy]);
+        assert!(output.status.success());
+        assert_eq!(
+            noted_count(&ctx.db_path, &shared),
+            Some(expected_count),
+            "Unexpected count for strategy {strategy}"
+        );
+    }
+}
+
+#[test]
+fn test_merge_dry_run_makes_no_changes() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let other_only = create_test_file(&ctx.working_path, "other_only.txt", "a");
+    note_path(
+        &other.db_path,
+        None,
+        other_only.to_str().unwrap(),
+        1,
+        &[],
+        &[],
+    );
+    let output_tag = memy_cmd_test_defaults(
+        &other.db_path,
+        None,
+        &["tag", "add", "docs", other_only.to_str().unwrap()],
+    );
+    assert!(output_tag.status.success());
+
+    let output = merge(&ctx, &other, &["--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("add "), "Unexpected dry run: {stdout}");
+    assert!(
+        stdout.contains("add tag docs to"),
+        "Unexpected dry run: {stdout}"
+    );
+    assert!(
+        stdout.contains("Would merge: 1 added"),
+        "Unexpected dry run: {stdout}"
+    );
+
+    assert_eq!(noted_count(&ctx.db_path, &other_only), None);
+
+    let output_real = merge(&ctx, &other, &[]);
+    assert!(output_real.status.success());
+    let tagged = list_paths(&ctx.db_path, None, &[], &["--tag", "docs"]);
+    assert_lines_eq(&tagged, &[other_only.to_str().unwrap()]);
+}
+
+#[test]
+fn test_merge_old_version_database_left_unmigrated() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "file.txt", "a");
+    let other_file = other.db_path.join("memy.sqlite3");
+    let conn = Connection::open(&other_file).unwrap();
+    conn.execute_batch(&format!(
+        "CREATE TABLE paths (
+            path TEXT PRIMARY KEY,
+            noted_count INTEGER NOT NULL,
+            last_noted_timestamp INTEGER NOT NULL
+        );
+        INSERT INTO paths VALUES ('{}', 4, 1700000000);
+        PRAGMA user_version = 1;",
+        file.to_str().unwrap()
+    ))
+    .unwrap();
+    conn.close().unwrap();
+
+    let output = merge(&ctx, &other, &[]);
+    assert!(
+        output.status.success(),
+        "Merge failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(noted_count(&ctx.db_path, &file), Some(4));
+
+    let check = Connection::open(&other_file).unwrap();
+    let version: i32 = check
+        .query_row("PRAGMA user_version;", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(version, 1, "The other database should not be migrated");
+}
+
+#[test]
+fn test_merge_rejects_newer_version_database() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    execute_sql(&other.db_path, "PRAGMA user_version = 999;");
+
+    let output = merge(&ctx, &other, &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Database version mismatch"));
+}
+
+#[test]
+fn test_merge_missing_database() {
+    let ctx = TestContext::new();
+    let other = TestContext::new();
+
+    let output = merge(&ctx, &other, &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not exist"));
+}
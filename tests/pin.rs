@@ -0,0 +1,141 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+struct PinFixture {
+    ctx: TestContext,
+    frequent: std::path::PathBuf,
+    rare: std::path::PathBuf,
+}
+
+fn setup() -> PinFixture {
+    let ctx = TestContext::new();
+
+    let frequent = create_test_file(&ctx.working_path, "frequent.txt", "a");
+    let rare = create_test_file(&ctx.working_path, "rare.txt", "b");
+
+    note_path(&ctx.db_path, None, rare.to_str().unwrap(), 1, &[], &[]);
+    note_path(&ctx.db_path, None, frequent.to_str().unwrap(), 3, &[], &[]);
+
+    PinFixture {
+        ctx,
+        frequent,
+        rare,
+    }
+}
+
+fn pin(ctx: &TestContext, subcommand: &str, paths: &[&str]) -> std::process::Output {
+    let mut args = vec![subcommand];
+    args.extend(paths);
+    memy_cmd_test_defaults(&ctx.db_path, None, &args)
+}
+
+#[test]
+fn test_pinned_path_lists_first_in_both_orders() {
+    let fixture = setup();
+    let ctx = &fixture.ctx;
+
+    let before = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_path_before(&before, "frequent.txt", "rare.txt");
+
+    let output = pin(ctx, "pin", &[fixture.rare.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let descending = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_path_before(&descending, "rare.txt", "frequent.txt");
+
+    let ascending = list_paths(&ctx.db_path, None, &[], &["--sort", "ascending"]);
+    assert_path_before(&ascending, "rare.txt", "frequent.txt");
+
+    let output_unpin = pin(ctx, "unpin", &[fixture.rare.to_str().unwrap()]);
+    assert!(output_unpin.status.success());
+
+    let after = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_path_before(&after, "frequent.txt", "rare.txt");
+}
+
+#[test]
+fn test_pin_without_paths_lists_pinned() {
+    let fixture = setup();
+    let ctx = &fixture.ctx;
+
+    let output = pin(ctx, "pin", &[fixture.rare.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let listed = pin(ctx, "pin", &[]);
+    let stdout = String::from_utf8(listed.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_lines_eq(&lines, &[fixture.rare.to_str().unwrap()]);
+}
+
+#[test]
+fn test_pin_unknown_path_fails() {
+    let fixture = setup();
+    let ctx = &fixture.ctx;
+
+    let unknown = create_test_file(&ctx.working_path, "unknown.txt", "c");
+    let output = pin(
+        ctx,
+        "pin",
+        &[fixture.rare.to_str().unwrap(), unknown.to_str().unwrap()],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown.txt"), "stderr was: {stderr}");
+
+    let descending = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_path_before(&descending, "frequent.txt", "rare.txt");
+}
+
+#[test]
+fn test_pinned_field_in_json_and_csv() {
+    let fixture = setup();
+    let ctx = &fixture.ctx;
+
+    let output = pin(ctx, "pin", &[fixture.rare.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let json_output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--format", "json"]);
+    let json: Value = serde_json::from_slice(&json_output.stdout).unwrap();
+    let entries = json.as_array().unwrap();
+    assert_eq!(entries[0]["path"], fixture.rare.to_str().unwrap());
+    assert_eq!(entries[0]["pinned"], true);
+    assert_eq!(entries[1]["path"], fixture.frequent.to_str().unwrap());
+    assert_eq!(entries[1]["pinned"], false);
+
+    let csv_output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--format", "csv"]);
+    let csv = String::from_utf8(csv_output.stdout).unwrap();
+    let header = csv.lines().next().unwrap();
+    assert!(header.contains(",pinned"), "header was: {header}");
+}
+
+#[test]
+#[cfg(not(target_os = "macos"))]
+fn test_pinned_marker_only_on_terminal() {
+    let fixture = setup();
+    let ctx = &fixture.ctx;
+
+    let output = pin(ctx, "pin", &[fixture.rare.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let terminal_output = memy_cmd_force_terminal(
+        Some(&ctx.db_path),
+        None,
+        &["--config", "import_on_first_use=false", "list"],
+        vec![],
+    );
+    let terminal_stdout = String::from_utf8_lossy(&terminal_output.stdout);
+    assert!(
+        terminal_stdout.contains("(pinned)"),
+        "Expected pinned marker on terminal, got: {terminal_stdout}"
+    );
+
+    let piped = list_paths(&ctx.db_path, None, &[], &[]);
+    assert!(
+        piped.iter().all(|line| !line.contains("(pinned)")),
+        "Marker should not be shown when piped: {piped:?}"
+    );
+}
@@ -0,0 +1,147 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+#[test]
+fn test_list_filtered_by_source() {
+    let ctx = TestContext::new();
+
+    let editor_file = create_test_file(&ctx.working_path, "edited.txt", "a");
+    let shell_file = create_test_file(&ctx.working_path, "listed.txt", "b");
+    let unsourced_file = create_test_file(&ctx.working_path, "plain.txt", "c");
+
+    note_path(
+        &ctx.db_path,
+        None,
+        editor_file.to_str().unwrap(),
+        1,
+        &[],
+        &["--source", "nvim"],
+    );
+    note_path(
+        &ctx.db_path,
+        None,
+        shell_file.to_str().unwrap(),
+        1,
+        &[],
+        &["--source", "zsh"],
+    );
+    note_path(
+        &ctx.db_path,
+        None,
+        unsourced_file.to_str().unwrap(),
+        1,
+        &[],
+        &[],
+    );
+
+    let nvim_paths = list_paths(&ctx.db_path, None, &[], &["--source", "nvim"]);
+    assert_lines_eq(&nvim_paths, &[editor_file.to_str().unwrap()]);
+
+    let mut any_paths = list_paths(
+        &ctx.db_path,
+        None,
+        &[],
+        &["--source", "nvim", "--source", "zsh"],
+    );
+    any_paths.sort();
+    assert_lines_eq(
+        &any_paths,
+        &[editor_file.to_str().unwrap(), shell_file.to_str().unwrap()],
+    );
+
+    let all_paths = list_paths(&ctx.db_path, None, &[], &[]);
+    assert_eq!(all_paths.len(), 3);
+}
+
+#[test]
+fn test_list_source_includes_path_noted_from_several_sources() {
+    let ctx = TestContext::new();
+
+    let test_file = create_test_file(&ctx.working_path, "shared.txt", "a");
+
+    note_path(
+        &ctx.db_path,
+        None,
+        test_file.to_str().unwrap(),
+        1,
+        &[],
+        &["--source", "nvim"],
+    );
+    note_path(
+        &ctx.db_path,
+        None,
+        test_file.to_str().unwrap(),
+        1,
+        &[],
+        &["--source", "bash"],
+    );
+
+    for source in ["nvim", "bash"] {
+        let paths = list_paths(&ctx.db_path, None, &[], &["--source", source]);
+        assert_lines_eq(&paths, &[test_file.to_str().unwrap()]);
+    }
+
+    let no_paths = list_paths(&ctx.db_path, None, &[], &["--source", "fish"]);
+    assert_lines_eq(&no_paths, &[]);
+}
+
+#[test]
+fn test_note_empty_source_rejected() {
+    let ctx = TestContext::new();
+
+    let test_file = create_test_file(&ctx.working_path, "file.txt", "a");
+
+    let output = memy_cmd_test_defaults(
+        &ctx.db_path,
+        None,
+        &["note", "--source", "", test_file.to_str().unwrap()],
+    );
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_stats_notes_by_source() {
+    let ctx = TestContext::new();
+
+    let test_file = create_test_file(&ctx.working_path, "file.txt", "a");
+
+    note_path(
+        &ctx.db_path,
+        None,
+        test_file.to_str().unwrap(),
+        2,
+        &[],
+        &["--source", "nvim"],
+    );
+    note_path(&ctx.db_path, None, test_file.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["stats", "--format", "json"],
+        vec![],
+    );
+    let json: Value = serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(json["notes_by_source"]["nvim"].as_u64(), Some(2));
+    assert_eq!(json["notes_by_source"]["unknown"].as_u64(), Some(1));
+
+    let plain_output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["stats", "--format", "plain"],
+        vec![],
+    );
+    let stdout = String::from_utf8(plain_output.stdout).unwrap();
+    assert!(
+        stdout.contains("Notes by Source"),
+        "Expected source breakdown in output:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("nvim"),
+        "Expected nvim in output:\n{stdout}"
+    );
+}
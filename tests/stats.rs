@@ -240,3 +240,186 @@ fn test_memy_stats_json_excludes_charts() {
         "JSON output should not contain time chart"
     );
 }
+
+/// Notes `main.rs` three times and `lib.rs`, `README.md` and `Makefile` once each, all inside a
+/// git repository, plus `notes.txt` outside it.
+fn setup_breakdown() -> (TestContext, std::path::PathBuf) {
+    let ctx = TestContext::new();
+
+    let repo = create_test_directory(&ctx.working_path, "repo");
+    create_test_directory(&repo, ".git");
+    let src = create_test_directory(&repo, "src");
+
+    let main_rs = create_test_file(&src, "main.rs", "fn main() {}");
+    let lib_rs = create_test_file(&src, "lib.rs", "");
+    let readme = create_test_file(&repo, "README.md", "# Repo");
+    let makefile = create_test_file(&repo, "Makefile", "all:");
+    let notes = create_test_file(&ctx.working_path, "notes.txt", "notes");
+
+    note_path(&ctx.db_path, None, main_rs.to_str().unwrap(), 3, &[], &[]);
+    for path in [&lib_rs, &readme, &makefile, &notes] {
+        note_path(&ctx.db_path, None, path.to_str().unwrap(), 1, &[], &[]);
+    }
+
+    (ctx, repo)
+}
+
+#[test]
+fn test_memy_stats_plain_shows_breakdowns() {
+    let (ctx, _) = setup_breakdown();
+
+    let output = memy_cmd(Some(&ctx.db_path), None, &["stats"], vec![]);
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 in output");
+
+    for title in [
+        "Top Directories (by count):",
+        "Top Extensions (by count):",
+        "Top Kinds (by count):",
+        "Top Repositories (by count):",
+    ] {
+        assert!(
+            stdout.contains(title),
+            "Expected {title} in output:\n{stdout}"
+        );
+    }
+
+    let extensions: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Top Extensions"))
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .collect();
+    assert_eq!(extensions.len(), 4, "{extensions:?}");
+    assert!(extensions[0].starts_with("rs "), "{extensions:?}");
+    assert!(extensions[0].ends_with(" 4"), "{extensions:?}");
+    assert!(
+        extensions.iter().any(|line| line.starts_with("(none) ")),
+        "{extensions:?}"
+    );
+}
+
+#[test]
+fn test_memy_stats_json_includes_breakdowns() {
+    let (ctx, repo) = setup_breakdown();
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["stats", "--format", "json", "--top", "2"],
+        vec![("HOME", ctx.working_path.to_str().unwrap())],
+    );
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 in output");
+    let json: Value = serde_json::from_str(&stdout).expect("Output is not valid JSON");
+    let breakdown = &json["breakdown"];
+
+    assert_eq!(breakdown["weight"], "count");
+
+    // Paths count towards every directory they're in, up to the home directory
+    let directories = breakdown["directories"].as_array().unwrap();
+    assert_eq!(directories.len(), 2, "--top limits each breakdown");
+    assert_eq!(
+        directories[0]["name"],
+        repo.to_str().unwrap(),
+        "{directories:?}"
+    );
+    assert_eq!(directories[0]["paths"], 4);
+    assert_eq!(directories[0]["weight"].as_f64(), Some(6.0));
+    assert_eq!(
+        directories[1]["name"],
+        repo.join("src").to_str().unwrap(),
+        "{directories:?}"
+    );
+    assert_eq!(directories[1]["paths"], 2);
+    assert_eq!(directories[1]["weight"].as_f64(), Some(4.0));
+
+    let repositories = breakdown["repositories"].as_array().unwrap();
+    assert_eq!(repositories.len(), 1, "{repositories:?}");
+    assert_eq!(repositories[0]["name"], repo.to_str().unwrap());
+    assert_eq!(repositories[0]["paths"], 4);
+
+    let kinds = breakdown["kinds"].as_array().unwrap();
+    assert_eq!(kinds[0]["name"], "source");
+    assert_eq!(kinds[1]["name"], "document");
+}
+
+#[test]
+fn test_memy_stats_top_zero_leaves_out_breakdowns() {
+    let (ctx, _) = setup_breakdown();
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["stats", "--format", "json", "--top", "0"],
+        vec![],
+    );
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 in output");
+    let json: Value = serde_json::from_str(&stdout).expect("Output is not valid JSON");
+    assert!(json.get("breakdown").is_none(), "{json}");
+
+    let plain = memy_cmd(Some(&ctx.db_path), None, &["stats", "--top", "0"], vec![]);
+    assert!(!String::from_utf8_lossy(&plain.stdout).contains("Top "));
+}
+
+#[test]
+fn test_memy_stats_breakdown_weighted_by_frecency() {
+    let (ctx, _) = setup_breakdown();
+
+    let output = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["stats", "--format", "json", "--weight", "frecency"],
+        vec![],
+    );
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 in output");
+    let json: Value = serde_json::from_str(&stdout).expect("Output is not valid JSON");
+
+    assert_eq!(json["breakdown"]["weight"], "frecency");
+    for entry in json["breakdown"]["extensions"].as_array().unwrap() {
+        let weight = entry["weight"].as_f64().unwrap();
+        let paths = entry["paths"].as_f64().unwrap();
+        assert!(
+            weight > 0.0 && weight <= paths,
+            "Frecency scores are at most 1 per path: {entry:?}"
+        );
+    }
+
+    let plain = memy_cmd(
+        Some(&ctx.db_path),
+        None,
+        &["stats", "--weight", "frecency"],
+        vec![],
+    );
+    assert!(String::from_utf8_lossy(&plain.stdout).contains("Top Directories (by frecency):"));
+}
+
+#[test]
+fn test_memy_stats_heatmap() {
+    let ctx = TestContext::new();
+
+    let first = create_test_file(&ctx.working_path, "first.txt", "a");
+    let second = create_test_file(&ctx.working_path, "second.txt", "b");
+    note_path(&ctx.db_path, None, first.to_str().unwrap(), 2, &[], &[]);
+    note_path(&ctx.db_path, None, second.to_str().unwrap(), 1, &[], &[]);
+
+    let output = memy_cmd(Some(&ctx.db_path), None, &["stats", "--heatmap"], vec![]);
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 in output");
+
+    assert!(
+        stdout.contains("Notes by Day:"),
+        "Expected heatmap in output:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("(busiest day: 3)"),
+        "All three notes were made today:\n{stdout}"
+    );
+    assert!(
+        !stdout.contains("Time Distribution"),
+        "The heatmap replaces the time chart:\n{stdout}"
+    );
+    for weekday in ["Mon ", "Wed ", "Sun "] {
+        assert!(
+            stdout.lines().any(|line| line.starts_with(weekday)),
+            "Expected a {weekday}row:\n{stdout}"
+        );
+    }
+}
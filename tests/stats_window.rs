@@ -0,0 +1,203 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+const DAY: u64 = 86400;
+
+struct WindowFixture {
+    ctx: TestContext,
+    steady: std::path::PathBuf,
+    dropped: std::path::PathBuf,
+    fresh: std::path::PathBuf,
+}
+
+/// `steady` was noted once 10 days ago and three times just now, `dropped` twice 10 days ago and
+/// `fresh` once, for the first time, just now.
+fn setup() -> WindowFixture {
+    let ctx = TestContext::new();
+
+    let steady = create_test_file(&ctx.working_path, "a_steady.txt", "a");
+    let dropped = create_test_file(&ctx.working_path, "b_dropped.txt", "b");
+    let fresh = create_test_file(&ctx.working_path, "c_fresh.txt", "c");
+
+    note_path(&ctx.db_path, None, steady.to_str().unwrap(), 1, &[], &[]);
+    note_path(&ctx.db_path, None, dropped.to_str().unwrap(), 2, &[], &[]);
+    age_path_events_by(&ctx.db_path, &steady, 10 * DAY);
+    age_path_events_by(&ctx.db_path, &dropped, 10 * DAY);
+
+    note_path(&ctx.db_path, None, steady.to_str().unwrap(), 3, &[], &[]);
+    note_path(&ctx.db_path, None, fresh.to_str().unwrap(), 1, &[], &[]);
+
+    WindowFixture {
+        ctx,
+        steady,
+        dropped,
+        fresh,
+    }
+}
+
+fn stats(ctx: &TestContext, args: &[&str]) -> String {
+    let mut full_args = vec!["stats"];
+    full_args.extend(args);
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &full_args);
+    assert!(
+        output.status.success(),
+        "stats failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn stats_json(ctx: &TestContext, args: &[&str]) -> Value {
+    let mut full_args = vec!["--format", "json"];
+    full_args.extend(args);
+    serde_json::from_str(&stats(ctx, &full_args)).expect("Output is not valid JSON")
+}
+
+#[test]
+fn test_stats_window_counts_only_notes_inside_it() {
+    let fixture = setup();
+
+    let json = stats_json(&fixture.ctx, &["--newer-than", "7d"]);
+    assert_eq!(json["total_paths"], 2);
+    assert_eq!(
+        json["highest_count"]["path"],
+        fixture.steady.to_str().unwrap()
+    );
+    assert_eq!(json["highest_count"]["noted_count"], 3);
+    assert_eq!(json["window"]["paths_noted"], 2);
+    assert_eq!(json["window"]["new_paths"], 1);
+    assert_eq!(json["window"]["total_notes"], 4);
+    assert!(json["window"]["end"].is_null());
+    assert!(json.get("comparison").is_none());
+
+    let older = stats_json(&fixture.ctx, &["--older-than", "7d"]);
+    assert_eq!(older["total_paths"], 2);
+    assert_eq!(older["window"]["total_notes"], 3);
+    assert_eq!(older["window"]["new_paths"], 2);
+    assert!(older["window"]["start"].is_null());
+}
+
+#[test]
+fn test_stats_whole_database_has_no_window() {
+    let fixture = setup();
+
+    let json = stats_json(&fixture.ctx, &[]);
+    assert_eq!(json["total_paths"], 3);
+    assert!(json.get("window").is_none());
+    assert!(!stats(&fixture.ctx, &[]).contains("Window:"));
+}
+
+#[test]
+fn test_stats_compare_previous() {
+    let fixture = setup();
+
+    let json = stats_json(
+        &fixture.ctx,
+        &["--newer-than", "7d", "--compare", "previous"],
+    );
+    let previous = &json["comparison"]["previous"];
+    assert_eq!(previous["paths_noted"], 2);
+    assert_eq!(previous["new_paths"], 2);
+    assert_eq!(previous["total_notes"], 3);
+
+    let movers: Vec<(String, i64)> = json["comparison"]["movers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| {
+            (
+                m["path"].as_str().unwrap().to_owned(),
+                m["change"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        movers,
+        [
+            (fixture.steady.to_str().unwrap().to_owned(), 2),
+            (fixture.dropped.to_str().unwrap().to_owned(), -2),
+            (fixture.fresh.to_str().unwrap().to_owned(), 1),
+        ]
+    );
+
+    let plain = stats(
+        &fixture.ctx,
+        &["--newer-than", "7d", "--compare", "previous"],
+    );
+    assert!(plain.contains("Paths Noted: 2 (+0)"), "{plain}");
+    assert!(plain.contains("New Paths: 1 (-1)"), "{plain}");
+    assert!(plain.contains("Total Notes: 4 (+1)"), "{plain}");
+    assert!(plain.contains("Compared With: "), "{plain}");
+    assert!(
+        plain.contains(&format!(
+            "  -2 {} (0 notes, previously 2)",
+            fixture.dropped.display()
+        )),
+        "{plain}"
+    );
+}
+
+#[test]
+fn test_stats_compare_needs_a_start() {
+    let fixture = setup();
+
+    let output = memy_cmd_test_defaults(
+        &fixture.ctx.db_path,
+        None,
+        &["stats", "--older-than", "1d", "--compare", "previous"],
+    );
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_stats_window_before_upgrade_is_partial() {
+    let ctx = TestContext::new();
+    let file = create_test_file(&ctx.working_path, "old.txt", "a");
+
+    // A version 2 database only kept the latest of the four notes
+    let conn = rusqlite::Connection::open(ctx.db_path.join("memy.sqlite3")).unwrap();
+    conn.execute_batch(&format!(
+        "CREATE TABLE paths (
+             path TEXT PRIMARY KEY,
+             noted_count INTEGER NOT NULL,
+             last_noted_timestamp INTEGER NOT NULL
+         );
+         CREATE TABLE state (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+         INSERT INTO paths VALUES ('{}', 4, strftime('%s', 'now') - {});
+         PRAGMA user_version = 2;",
+        file.display(),
+        10 * DAY
+    ))
+    .unwrap();
+    conn.close().unwrap();
+
+    let upgraded = stats_json(&ctx, &["--newer-than", "30d"]);
+    assert!(
+        upgraded["window"]["partial_before"].is_string(),
+        "{upgraded}"
+    );
+    assert!(
+        stats(&ctx, &["--newer-than", "30d"]).contains("(partial: not every note before"),
+        "Windows before the upgrade should be marked partial"
+    );
+
+    // Once the upgrade is three days old, windows starting after it have every note
+    execute_sql(
+        &ctx.db_path,
+        &format!(
+            "UPDATE state SET value = CAST(value - {} AS TEXT) WHERE key = 'events_complete_since'",
+            3 * DAY
+        ),
+    );
+    let later = stats_json(&ctx, &["--newer-than", "2d", "--compare", "previous"]);
+    assert!(later["window"].get("partial_before").is_none(), "{later}");
+    assert!(
+        later["comparison"]["previous"]["partial_before"].is_string(),
+        "{later}"
+    );
+}
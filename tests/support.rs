@@ -141,12 +141,30 @@ pub fn memy_cmd(
     cmd.output().expect("Could not run memy")
 }
 
-pub fn memy_cmd_force_terminal(
+pub fn memy_cmd_in_dir(
     db_path: Option<&std::path::Path>,
     config_path: Option<&std::path::Path>,
+    current_dir: &std::path::Path,
     args: &[&str],
-    env_vars: Vec<(&str, &str)>,
 ) -> Output {
+    let invocation = build_memy_invocation(db_path, config_path, vec![]);
+
+    let mut cmd = Command::cargo_bin("memy").expect("Cannot set up memy command");
+    cmd.env("MEMY_DB_DIR", &invocation.db_dir);
+    cmd.env("MEMY_CONFIG_DIR", &invocation.config_dir);
+    cmd.current_dir(current_dir);
+    cmd.args(args);
+
+    cmd.output().expect("Could not run memy")
+}
+
+/// Builds a shell command line running memy with `args`, for `script` to run in a terminal.
+fn terminal_script(
+    db_path: Option<&std::path::Path>,
+    config_path: Option<&std::path::Path>,
+    args: &[&str],
+    env_vars: Vec<(&str, &str)>,
+) -> (MemyInvocation, String) {
     fn shell_quote(value: &str) -> String {
         format!("'{}'", value.replace('\'', "'\"'\"'"))
     }
@@ -166,8 +184,8 @@ pub fn memy_cmd_force_terminal(
         shell_quote(&invocation.config_dir.to_string_lossy())
     );
 
-    for (key, value) in invocation.env_vars {
-        let _ = write!(script, "{key}={} ", shell_quote(&value));
+    for (key, value) in &invocation.env_vars {
+        let _ = write!(script, "{key}={} ", shell_quote(value));
     }
 
     script.push_str(&shell_quote(&binary));
@@ -176,12 +194,60 @@ pub fn memy_cmd_force_terminal(
         script.push_str(&shell_quote(arg));
     }
 
+    (invocation, script)
+}
+
+pub fn memy_cmd_force_terminal(
+    db_path: Option<&std::path::Path>,
+    config_path: Option<&std::path::Path>,
+    args: &[&str],
+    env_vars: Vec<(&str, &str)>,
+) -> Output {
+    let (_invocation, script) = terminal_script(db_path, config_path, args, env_vars);
+
     std::process::Command::new("script")
         .args(["-qec", &script, "/dev/null"])
         .output()
         .expect("Could not run memy in terminal")
 }
 
+/// Runs memy in a 100x20 terminal, typing each of `keys` in turn with a pause before each one so
+/// memy can react. The output mixes everything memy drew on the terminal with its stdout.
+pub fn memy_cmd_terminal_with_keys(
+    db_path: Option<&std::path::Path>,
+    config_path: Option<&std::path::Path>,
+    args: &[&str],
+    env_vars: Vec<(&str, &str)>,
+    keys: &[&str],
+) -> Output {
+    let (_invocation, script) = terminal_script(db_path, config_path, args, env_vars);
+
+    let mut child = std::process::Command::new("script")
+        .args([
+            "-qec",
+            &format!("stty cols 100 rows 20; {script}"),
+            "/dev/null",
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Could not run memy in terminal");
+
+    let mut stdin = child.stdin.take().unwrap();
+    for key in keys {
+        sleep(500);
+        std::io::Write::write_all(&mut stdin, key.as_bytes()).unwrap();
+        std::io::Write::flush(&mut stdin).unwrap();
+    }
+    sleep(500);
+    drop(stdin);
+
+    child
+        .wait_with_output()
+        .expect("Could not run memy in terminal")
+}
+
 pub fn memy_cmd_test_defaults(
     db_path: &std::path::Path,
     config_path: Option<&std::path::Path>,
@@ -207,6 +273,18 @@ pub fn execute_sql(db_path: &std::path::Path, sql: &str) {
         .expect("failed to execute SQL command");
 }
 
+pub fn count_events(db_path: &std::path::Path, path: &std::path::Path) -> i64 {
+    let db_file = db_path.join("memy.sqlite3");
+    let connection = rusqlite::Connection::open(db_file).expect("failed to open database");
+    connection
+        .query_row(
+            "SELECT COUNT(*) FROM events WHERE path = ?1",
+            [path.to_str().unwrap()],
+            |row| row.get(0),
+        )
+        .expect("failed to count events")
+}
+
 pub fn note_path(
     db_path: &std::path::Path,
     config_path: Option<&std::path::Path>,
@@ -274,6 +352,19 @@ pub fn age_path_by(db_path: &std::path::Path, path: &std::path::Path, seconds: u
     );
 }
 
+/// Moves every note of `path` made so far `seconds` into the past, in its event history as well
+/// as its last noted timestamp.
+pub fn age_path_events_by(db_path: &std::path::Path, path: &std::path::Path, seconds: u64) {
+    age_path_by(db_path, path, seconds);
+    execute_sql(
+        db_path,
+        &format!(
+            "UPDATE events SET timestamp = timestamp - {seconds} WHERE path = '{}'",
+            path.to_str().unwrap()
+        ),
+    );
+}
+
 /// Notes each path in order, sleeping between each one.
 pub fn note_paths_with_delay(
     db_path: &std::path::Path,
@@ -0,0 +1,154 @@
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use serde_json::Value;
+
+fn tag(ctx: &TestContext, args: &[&str]) -> std::process::Output {
+    let mut full_args = vec!["tag"];
+    full_args.extend(args);
+    memy_cmd_test_defaults(&ctx.db_path, None, &full_args)
+}
+
+fn stdout_lines(output: std::process::Output) -> Vec<String> {
+    assert!(
+        output.status.success(),
+        "Command failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+#[test]
+fn test_list_filtered_by_tag() {
+    let ctx = TestContext::new();
+
+    let runbook = create_test_file(&ctx.working_path, "runbook.md", "a");
+    let terraform = create_test_file(&ctx.working_path, "main.tf", "b");
+    let untagged = create_test_file(&ctx.working_path, "other.txt", "c");
+    for path in [&runbook, &terraform, &untagged] {
+        note_path(&ctx.db_path, None, path.to_str().unwrap(), 1, &[], &[]);
+    }
+
+    stdout_lines(tag(
+        &ctx,
+        &[
+            "add",
+            "infra",
+            runbook.to_str().unwrap(),
+            terraform.to_str().unwrap(),
+        ],
+    ));
+    stdout_lines(tag(&ctx, &["add", "docs", runbook.to_str().unwrap()]));
+
+    let mut infra = list_paths(&ctx.db_path, None, &[], &["--tag", "infra"]);
+    infra.sort();
+    assert_lines_eq(
+        &infra,
+        &[terraform.to_str().unwrap(), runbook.to_str().unwrap()],
+    );
+
+    let both = list_paths(
+        &ctx.db_path,
+        None,
+        &[],
+        &["--tag", "infra", "--tag", "docs"],
+    );
+    assert_lines_eq(&both, &[runbook.to_str().unwrap()]);
+
+    let none = list_paths(&ctx.db_path, None, &[], &["--tag", "client-x"]);
+    assert_lines_eq(&none, &[]);
+}
+
+#[test]
+fn test_tag_remove_and_list() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "file.txt", "a");
+    let other = create_test_file(&ctx.working_path, "other.txt", "b");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+    note_path(&ctx.db_path, None, other.to_str().unwrap(), 1, &[], &[]);
+
+    stdout_lines(tag(&ctx, &["add", "infra", file.to_str().unwrap()]));
+    stdout_lines(tag(&ctx, &["add", "docs", file.to_str().unwrap()]));
+    stdout_lines(tag(&ctx, &["add", "client-x", other.to_str().unwrap()]));
+
+    let all_tags = stdout_lines(tag(&ctx, &["list"]));
+    assert_lines_eq(&all_tags, &["client-x", "docs", "infra"]);
+
+    let file_tags = stdout_lines(tag(&ctx, &["list", file.to_str().unwrap()]));
+    assert_lines_eq(&file_tags, &["docs", "infra"]);
+
+    stdout_lines(tag(&ctx, &["remove", "infra", file.to_str().unwrap()]));
+    let remaining = stdout_lines(tag(&ctx, &["list", file.to_str().unwrap()]));
+    assert_lines_eq(&remaining, &["docs"]);
+
+    let infra = list_paths(&ctx.db_path, None, &[], &["--tag", "infra"]);
+    assert_lines_eq(&infra, &[]);
+}
+
+#[test]
+fn test_tag_unknown_path_fails() {
+    let ctx = TestContext::new();
+
+    let unknown = create_test_file(&ctx.working_path, "unknown.txt", "a");
+    let output = tag(&ctx, &["add", "docs", unknown.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Not in the database"));
+}
+
+#[test]
+fn test_tag_invalid_name_rejected() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "file.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+
+    for invalid in ["", "two words", "a;b", "a,b"] {
+        let output = tag(&ctx, &["add", invalid, file.to_str().unwrap()]);
+        assert!(
+            !output.status.success(),
+            "Tag '{invalid}' should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_tags_in_json_and_csv() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "file.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+    stdout_lines(tag(&ctx, &["add", "infra", file.to_str().unwrap()]));
+    stdout_lines(tag(&ctx, &["add", "docs", file.to_str().unwrap()]));
+
+    let json_output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--format", "json"]);
+    let json: Value = serde_json::from_slice(&json_output.stdout).unwrap();
+    assert_eq!(json[0]["tags"], serde_json::json!(["docs", "infra"]));
+
+    let csv_output = memy_cmd_test_defaults(&ctx.db_path, None, &["list", "--format", "csv"]);
+    let csv = String::from_utf8(csv_output.stdout).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().ends_with(",tags"));
+    assert!(lines.next().unwrap().ends_with(",docs;infra"));
+}
+
+#[test]
+fn test_forget_removes_tags() {
+    let ctx = TestContext::new();
+
+    let file = create_test_file(&ctx.working_path, "file.txt", "a");
+    note_path(&ctx.db_path, None, file.to_str().unwrap(), 1, &[], &[]);
+    stdout_lines(tag(&ctx, &["add", "infra", file.to_str().unwrap()]));
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["forget", file.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let all_tags = stdout_lines(tag(&ctx, &["list"]));
+    assert_lines_eq(&all_tags, &[]);
+}
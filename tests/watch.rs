@@ -0,0 +1,183 @@
+#![cfg(target_os = "linux")]
+#![allow(clippy::unwrap_used, reason = "unwrap() OK inside tests")]
+
+mod support;
+use support::*;
+
+use std::io::{BufRead as _, BufReader};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+
+/// A `memy watch` running in the background, stopped when dropped.
+struct Watch {
+    child: Child,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _: std::io::Result<()> = self.child.kill();
+        let _: std::io::Result<ExitStatus> = self.child.wait();
+    }
+}
+
+fn start_watch(ctx: &TestContext, args: &[&str]) -> Watch {
+    let mut watch = Watch {
+        child: Command::new(assert_cmd::cargo::cargo_bin("memy"))
+            .env("MEMY_DB_DIR", &ctx.db_path)
+            .env("MEMY_CONFIG_DIR", &ctx.config_path)
+            .env_remove("RUST_LOG")
+            .args(["-v", "--config", "import_on_first_use=false"])
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap(),
+    };
+
+    let stderr = watch.child.stderr.take().unwrap();
+    let mut lines = BufReader::new(stderr).lines();
+    assert!(
+        lines.any(|line| line.unwrap().contains("Watching")),
+        "memy watch did not start"
+    );
+    // Keep draining the log so memy watch never blocks writing it
+    std::thread::spawn(move || lines.for_each(drop));
+
+    watch
+}
+
+fn is_noted(ctx: &TestContext, path: &Path) -> bool {
+    memy_cmd_test_defaults(&ctx.db_path, None, &["explain", path.to_str().unwrap()])
+        .status
+        .success()
+}
+
+fn wait_until_noted(ctx: &TestContext, path: &Path) -> bool {
+    (0..50).any(|_| {
+        sleep(100);
+        is_noted(ctx, path)
+    })
+}
+
+fn watch_args<'a>(ctx: &'a TestContext, extra: &[&'a str]) -> Vec<&'a str> {
+    let mut args = vec!["watch", "--debounce", "200ms"];
+    args.extend(extra);
+    args.push(ctx.working_path.to_str().unwrap());
+    args
+}
+
+#[test]
+fn test_watch_notes_written_files_once() {
+    let ctx = TestContext::new();
+    let subdir = create_test_directory(&ctx.working_path, "sub");
+    let _watch = start_watch(&ctx, &watch_args(&ctx, &[]));
+
+    let file = subdir.join("written.txt");
+    for contents in ["a", "b", "c", "d"] {
+        std::fs::write(&file, contents).unwrap();
+    }
+
+    assert!(wait_until_noted(&ctx, &file));
+    sleep(500);
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["explain", file.to_str().unwrap()]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Noted count: 1"));
+
+    let sources = list_paths(&ctx.db_path, None, &[], &["--source", "watch"]);
+    assert_eq!(sources, [file.to_str().unwrap()]);
+}
+
+#[test]
+fn test_watch_notes_read_files() {
+    let ctx = TestContext::new();
+    let file = create_test_file(&ctx.working_path, "read.txt", "contents");
+    let _watch = start_watch(&ctx, &watch_args(&ctx, &[]));
+
+    std::fs::read_to_string(&file).unwrap();
+
+    assert!(wait_until_noted(&ctx, &file));
+}
+
+#[test]
+fn test_watch_writes_only_ignores_reads() {
+    let ctx = TestContext::new();
+    let file = create_test_file(&ctx.working_path, "read.txt", "contents");
+    let _watch = start_watch(&ctx, &watch_args(&ctx, &["--writes-only"]));
+
+    std::fs::read_to_string(&file).unwrap();
+    let marker = create_test_file(&ctx.working_path, "marker.txt", "m");
+
+    assert!(wait_until_noted(&ctx, &marker));
+    assert!(!is_noted(&ctx, &file));
+}
+
+#[test]
+fn test_watch_excludes_build_directories() {
+    let ctx = TestContext::new();
+    let target = create_test_directory(&ctx.working_path, "target");
+    let extra = create_test_directory(&ctx.working_path, "generated");
+    let _watch = start_watch(&ctx, &watch_args(&ctx, &["--exclude", "generated"]));
+
+    let built = create_test_file(&target, "built.o", "o");
+    let generated = create_test_file(&extra, "out.txt", "g");
+    let node_modules = ctx.working_path.join("node_modules");
+    std::fs::create_dir(&node_modules).unwrap();
+    sleep(100);
+    let dependency = create_test_file(&node_modules, "index.js", "js");
+
+    // New directories are watched as they appear, unless excluded
+    let new_dir = create_test_directory(&ctx.working_path, "new");
+    sleep(100);
+    let marker = create_test_file(&new_dir, "marker.txt", "m");
+
+    assert!(wait_until_noted(&ctx, &marker));
+    assert!(!is_noted(&ctx, &built));
+    assert!(!is_noted(&ctx, &generated));
+    assert!(!is_noted(&ctx, &dependency));
+}
+
+#[test]
+fn test_watch_applies_denylist() {
+    let ctx = TestContext::new();
+    let _watch = start_watch(
+        &ctx,
+        &watch_args(&ctx, &["--config", "denylist=[\"*.log\"]"]),
+    );
+
+    let log = create_test_file(&ctx.working_path, "debug.log", "l");
+    let marker = create_test_file(&ctx.working_path, "marker.txt", "m");
+
+    assert!(wait_until_noted(&ctx, &marker));
+    assert!(!is_noted(&ctx, &log));
+}
+
+#[test]
+fn test_watch_rejects_missing_directory() {
+    let ctx = TestContext::new();
+    let missing = ctx.working_path.join("missing");
+
+    let output = memy_cmd_test_defaults(&ctx.db_path, None, &["watch", missing.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot watch"));
+}
+
+#[test]
+fn test_watch_retries_when_database_is_locked() {
+    let ctx = TestContext::new();
+    let mut watch = start_watch(&ctx, &watch_args(&ctx, &[]));
+    let marker = create_test_file(&ctx.working_path, "marker.txt", "m");
+    assert!(wait_until_noted(&ctx, &marker));
+
+    let lock = rusqlite::Connection::open(ctx.db_path.join("memy.sqlite3")).unwrap();
+    lock.execute_batch("BEGIN EXCLUSIVE").unwrap();
+    let file = create_test_file(&ctx.working_path, "locked.txt", "l");
+
+    // Long enough for memy watch to give up waiting for the lock
+    sleep(6500);
+    assert!(
+        watch.child.try_wait().unwrap().is_none(),
+        "memy watch should keep running"
+    );
+
+    lock.execute_batch("ROLLBACK").unwrap();
+    assert!(wait_until_noted(&ctx, &file));
+}